@@ -2,18 +2,50 @@
 
 extern crate test;
 
-use publicsuffix::{List, Psl};
+use publicsuffix::{labels_bytes, List, Psl, StaticList};
+use std::sync::LazyLock;
 use test::Bencher;
 
-lazy_static::lazy_static! {
-    static ref LIST: List = include_str!("../tests/public_suffix_list.dat").parse().unwrap();
-}
+static LIST: LazyLock<List> = LazyLock::new(|| {
+    include_str!("../tests/public_suffix_list.dat")
+        .parse()
+        .unwrap()
+});
 
 const DOMAIN: &[u8] = b"www.example.com";
 
 #[bench]
 fn bench_find(b: &mut Bencher) {
-    b.iter(|| LIST.find(DOMAIN.rsplit(|x| *x == b'.')));
+    b.iter(|| LIST.find(labels_bytes(DOMAIN)));
+}
+
+const DEEP_PRIVATE: &[u8] = b"a.b.foo.bar.platformsh.site";
+
+#[bench]
+fn bench_find_deep_private(b: &mut Bencher) {
+    b.iter(|| LIST.find(labels_bytes(DEEP_PRIVATE)));
+}
+
+const SINGLE_LABEL: &[u8] = b"localhost";
+
+#[bench]
+fn bench_find_single_label(b: &mut Bencher) {
+    b.iter(|| LIST.find(labels_bytes(SINGLE_LABEL)));
+}
+
+const KNOWN_TLD: &[u8] = b"com";
+
+#[bench]
+fn bench_find_known_tld(b: &mut Bencher) {
+    b.iter(|| LIST.find(labels_bytes(KNOWN_TLD)));
+}
+
+static BLOB: LazyLock<Vec<u8>> = LazyLock::new(|| StaticList::build(&LIST).unwrap());
+
+#[bench]
+fn bench_find_static(b: &mut Bencher) {
+    let list = StaticList::new(&BLOB).unwrap();
+    b.iter(|| list.find(labels_bytes(DOMAIN)));
 }
 
 #[bench]
@@ -25,3 +57,63 @@ fn bench_suffix(b: &mut Bencher) {
 fn bench_domain(b: &mut Bencher) {
     b.iter(|| LIST.domain(DOMAIN).unwrap());
 }
+
+static HOSTS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    let tlds = ["com", "co.uk", "kawasaki.jp", "github.io", "gafregsrse"];
+    (0..1000)
+        .map(|i| format!("www{}.example.{}", i, tlds[i % tlds.len()]))
+        .collect()
+});
+
+#[bench]
+fn bench_find_loop(b: &mut Bencher) {
+    b.iter(|| {
+        HOSTS
+            .iter()
+            .map(|host| LIST.find(labels_bytes(host.as_bytes())))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[bench]
+fn bench_find_batch(b: &mut Bencher) {
+    b.iter(|| LIST.find_batch(HOSTS.iter().map(String::as_str)));
+}
+
+// the same hosts over and over, which the cache remembers after the first pass
+#[bench]
+fn bench_find_repeated(b: &mut Bencher) {
+    b.iter(|| {
+        HOSTS
+            .iter()
+            .take(100)
+            .map(|host| LIST.find(labels_bytes(host.as_bytes())))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[cfg(feature = "cache")]
+#[bench]
+fn bench_find_repeated_cached(b: &mut Bencher) {
+    let list =
+        publicsuffix::CachedList::new(LIST.clone(), std::num::NonZeroUsize::new(100).unwrap());
+    b.iter(|| {
+        HOSTS
+            .iter()
+            .take(100)
+            .map(|host| list.find(labels_bytes(host.as_bytes())))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[bench]
+fn bench_parse(b: &mut Bencher) {
+    let list = include_str!("../tests/public_suffix_list.dat");
+    b.iter(|| list.parse::<List>().unwrap());
+}
+
+#[bench]
+fn bench_from_bytes(b: &mut Bencher) {
+    let list = include_bytes!("../tests/public_suffix_list.dat");
+    b.iter(|| List::from_bytes(list).unwrap());
+}
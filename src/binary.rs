@@ -0,0 +1,207 @@
+//! A compact binary encoding of the list
+//!
+//! The encoding starts with the 4 byte magic `PSL\0` followed by a format
+//! version byte. Then come the list metadata, including the owners of the
+//! private rules, and a preorder dump of the rule tree. All integers are
+//! little endian.
+
+use crate::{label, Error, Interner, Leaf, List, ListMeta, Node, Type};
+use std::convert::TryFrom;
+use std::io::{ErrorKind, Read, Write};
+
+const MAGIC: &[u8; 4] = b"PSL\0";
+const VERSION: u8 = 2;
+
+// Rules can't be deeper than a domain name can have labels
+const MAX_DEPTH: usize = 128;
+
+const LEAF: u8 = 0b001;
+const PRIVATE: u8 = 0b010;
+const EXCEPTION: u8 = 0b100;
+
+impl List {
+    /// Writes the list in a compact binary format
+    ///
+    /// This is much faster to [`List::load`] than parsing the list again.
+    /// The format is versioned, so files written by a different version
+    /// of the format are detected when loading them.
+    ///
+    /// Only the rules and the metadata are saved. The section the list is
+    /// restricted to, its [length limit](List::with_max_input_len) and
+    /// [whether it allows underscores](List::with_underscores) are not, so
+    /// a loaded list goes back to the defaults for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if writing fails.
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        write_str(&mut writer, self.meta.version.as_deref())?;
+        write_str(&mut writer, self.meta.fetched.as_deref())?;
+        writer.write_all(&len_u32(self.meta.owners.len())?.to_le_bytes())?;
+        for (owner, rules) in &self.meta.owners {
+            write_str(&mut writer, Some(owner))?;
+            writer.write_all(&len_u32(rules.len())?.to_le_bytes())?;
+            for rule in rules {
+                write_str(&mut writer, Some(rule))?;
+            }
+        }
+        write_node(&mut writer, &self.rules)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a list written by [`List::save`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedFormat` if the input was not written by
+    /// this version of the format, `Error::InvalidList` if it is corrupt
+    /// or cut short and `Error::Io` if reading fails.
+    pub fn load<R: Read>(reader: R) -> Result<Self, Error> {
+        read_list(reader).map_err(|error| match error {
+            Error::Io(ErrorKind::UnexpectedEof) => Error::InvalidList,
+            error => error,
+        })
+    }
+}
+
+fn read_list<R: Read>(mut reader: R) -> Result<List, Error> {
+    let mut header = [0; 5];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != MAGIC || header[4] != VERSION {
+        return Err(Error::UnsupportedFormat);
+    }
+    let mut list = List::new();
+    list.meta = ListMeta {
+        version: read_str(&mut reader)?,
+        fetched: read_str(&mut reader)?,
+        owners: Vec::new(),
+    };
+    for _ in 0..read_u32(&mut reader)? {
+        let owner = read_str(&mut reader)?.ok_or(Error::InvalidList)?;
+        let rules = (0..read_u32(&mut reader)?)
+            .map(|_| read_str(&mut reader)?.ok_or(Error::InvalidList))
+            .collect::<Result<_, _>>()?;
+        list.meta.owners.push((owner, rules));
+    }
+    read_node(&mut reader, &mut list.rules, &mut Interner::default(), 0)?;
+    Ok(list)
+}
+
+fn write_str<W: Write>(writer: &mut W, s: Option<&str>) -> Result<(), Error> {
+    match s {
+        Some(s) => {
+            writer.write_all(&[1])?;
+            writer.write_all(&len_u32(s.len())?.to_le_bytes())?;
+            writer.write_all(s.as_bytes())?;
+        }
+        None => writer.write_all(&[0])?,
+    }
+    Ok(())
+}
+
+fn write_node<W: Write>(writer: &mut W, node: &Node) -> Result<(), Error> {
+    let flags = match node.leaf {
+        Some(leaf) => {
+            let mut flags = LEAF;
+            if leaf.typ == Type::Private {
+                flags |= PRIVATE;
+            }
+            if leaf.is_exception {
+                flags |= EXCEPTION;
+            }
+            flags
+        }
+        None => 0,
+    };
+    writer.write_all(&[flags])?;
+    writer.write_all(&len_u32(node.children.len())?.to_le_bytes())?;
+    let mut children = node
+        .children
+        .iter()
+        .map(|(key, child)| (label(key), child))
+        .collect::<Vec<_>>();
+    children.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    for (label, child) in children {
+        let len = u8::try_from(label.len()).map_err(|_| Error::InvalidRule(label.to_owned()))?;
+        writer.write_all(&[len])?;
+        writer.write_all(label.as_bytes())?;
+        write_node(writer, child)?;
+    }
+    Ok(())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> Result<u8, Error> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_str<R: Read>(reader: &mut R) -> Result<Option<String>, Error> {
+    match read_u8(reader)? {
+        0 => Ok(None),
+        1 => {
+            let len = read_u32(reader)?;
+            let mut bytes = Vec::new();
+            reader.take(u64::from(len)).read_to_end(&mut bytes)?;
+            if bytes.len() != len as usize {
+                return Err(Error::InvalidList);
+            }
+            String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|_| Error::InvalidList)
+        }
+        _ => Err(Error::InvalidList),
+    }
+}
+
+fn read_node<R: Read>(
+    reader: &mut R,
+    node: &mut Node,
+    keys: &mut Interner,
+    depth: usize,
+) -> Result<(), Error> {
+    if depth > MAX_DEPTH {
+        return Err(Error::InvalidList);
+    }
+    let flags = read_u8(reader)?;
+    if flags & !(LEAF | PRIVATE | EXCEPTION) != 0 {
+        return Err(Error::InvalidList);
+    }
+    if flags & LEAF != 0 {
+        node.leaf = Some(Leaf {
+            is_exception: flags & EXCEPTION != 0,
+            typ: if flags & PRIVATE != 0 {
+                Type::Private
+            } else {
+                Type::Icann
+            },
+            #[cfg(feature = "sources")]
+            source: None,
+        });
+    }
+    for _ in 0..read_u32(reader)? {
+        let mut label = [0; u8::MAX as usize];
+        let label = &mut label[..usize::from(read_u8(reader)?)];
+        reader.read_exact(label)?;
+        let label = std::str::from_utf8(label).map_err(|_| Error::InvalidList)?;
+        if label.is_empty() {
+            return Err(Error::InvalidList);
+        }
+        let child = node.child_mut(keys.key(label));
+        read_node(reader, child, keys, depth + 1)?;
+    }
+    Ok(())
+}
+
+fn len_u32(len: usize) -> Result<u32, Error> {
+    u32::try_from(len).map_err(|_| Error::InvalidList)
+}
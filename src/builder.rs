@@ -0,0 +1,72 @@
+//! Building a list one rule at a time
+
+use crate::{Error, List, Parser, Type};
+
+/// Builds a list from rules added one at a time
+///
+/// This tracks sections the way parsing a list does, so code generators
+/// can add rules without writing out a list first. Rules go through the
+/// same checks as parsed ones.
+///
+/// ```
+/// use publicsuffix::{ListBuilder, Psl};
+///
+/// let list = ListBuilder::new()
+///     .begin_icann()
+///     .rule("uk")?
+///     .rule("co.uk")?
+///     .begin_private()
+///     .rule("github.io")?
+///     .build()?;
+/// assert_eq!(list.suffix(b"www.example.co.uk").unwrap(), "co.uk");
+/// # Ok::<(), publicsuffix::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct ListBuilder {
+    parser: Parser,
+}
+
+impl ListBuilder {
+    /// Creates a builder with no rules and no section started
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the rules that follow to the ICANN section
+    #[inline]
+    pub fn begin_icann(&mut self) -> &mut Self {
+        self.parser.typ = Some(Type::Icann);
+        self
+    }
+
+    /// Adds the rules that follow to the private section
+    #[inline]
+    pub fn begin_private(&mut self) -> &mut Self {
+        self.parser.typ = Some(Type::Private);
+        self
+    }
+
+    /// Adds a rule, written like in a list, to the current section
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RuleBeforeSection` if no section was started yet
+    /// and an `Err` if the rule is invalid.
+    #[inline]
+    pub fn rule(&mut self, rule: &str) -> Result<&mut Self, Error> {
+        self.parser.rule(rule)?;
+        Ok(self)
+    }
+
+    /// Takes the list built so far, leaving the builder empty
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidList` if no rules were added.
+    #[inline]
+    pub fn build(&mut self) -> Result<List, Error> {
+        core::mem::take(&mut self.parser).finish()
+    }
+}
@@ -0,0 +1,99 @@
+//! Remembering the suffixes of recently looked up names
+
+use crate::{Info, List, Psl};
+use core::num::NonZeroUsize;
+use lru::LruCache;
+use std::sync::Mutex;
+
+/// A list that remembers the suffixes of the names it looked up last
+///
+/// Lookups of names seen recently skip walking the rules, which helps when
+/// the same hosts come up again and again, e.g. when routing requests per
+/// tenant. Walking the rules is already cheap though, so measure before
+/// relying on this; it pays off most with the `anycase` or `unicode`
+/// features and for long names. Names are remembered as they are given, so
+/// names differing in case or by a trailing dot are remembered separately.
+/// The cache is behind a lock, so the list can be shared between threads.
+/// The lock is only held to look names up in the cache and to remember
+/// them, not while walking the rules.
+#[derive(Debug)]
+pub struct CachedList {
+    list: List,
+    cache: Mutex<LruCache<Vec<u8>, Info>>,
+}
+
+impl CachedList {
+    /// Wraps `list`, remembering the suffixes of up to `capacity` names
+    #[must_use]
+    pub fn new(list: List, capacity: NonZeroUsize) -> Self {
+        Self {
+            list,
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// The number of names remembered
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Checks to see if no names are remembered
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets all the names remembered
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Takes the list out, dropping the cache
+    #[must_use]
+    pub fn into_inner(self) -> List {
+        self.list
+    }
+
+    // a lookup that panicked can't leave the cache half updated
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<Vec<u8>, Info>> {
+        self.cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl AsRef<List> for CachedList {
+    #[inline]
+    fn as_ref(&self) -> &List {
+        &self.list
+    }
+}
+
+impl Psl for CachedList {
+    fn find<'a, T>(&self, labels: T) -> Info
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        let mut labels = labels.peekable();
+        if labels.peek().is_none() {
+            return self.list.find(labels);
+        }
+        // the labels joined in the order they come in, which they can be
+        // split back into
+        let mut key = Vec::new();
+        for (i, label) in labels.enumerate() {
+            if i > 0 {
+                key.push(b'.');
+            }
+            key.extend_from_slice(label);
+        }
+        if let Some(info) = self.lock().get(key.as_slice()) {
+            return *info;
+        }
+        // other lookups can use the cache while this one walks the rules
+        let info = self.list.find(key.split(|x| *x == b'.'));
+        self.lock().put(key, info);
+        info
+    }
+}
@@ -0,0 +1,91 @@
+//! Building lists from JSON and TOML documents
+
+use crate::{Error, Interner, List, Type};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::Deserialize;
+
+/// The rules of a list, by section
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Sections {
+    #[serde(default)]
+    icann: Vec<String>,
+    #[serde(default)]
+    private: Vec<String>,
+}
+
+impl List {
+    /// Creates a new list from a JSON document
+    ///
+    /// The document is an object with an `icann` and a `private` array of
+    /// rules, written the same way as in the `.dat` format. Either array
+    /// can be left out.
+    ///
+    /// ```
+    /// # use publicsuffix::List;
+    /// let list = List::from_json(
+    ///     r#"{ "icann": ["internal"], "private": ["team.internal"] }"#,
+    /// )?;
+    /// assert!(list.suffix_is_private("www.team.internal"));
+    /// # Ok::<(), publicsuffix::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if the document is malformed, giving
+    /// the line and column of the problem, or if a rule is invalid, giving
+    /// its section and index. Returns `Error::InvalidList` if there are no
+    /// rules at all.
+    pub fn from_json(s: &str) -> Result<Self, Error> {
+        let sections =
+            serde_json::from_str(s).map_err(|error| Error::InvalidConfig(error.to_string()))?;
+        Self::from_sections(sections)
+    }
+
+    /// Creates a new list from a TOML document
+    ///
+    /// This takes the same `icann` and `private` arrays as
+    /// [`List::from_json`], as top-level keys.
+    ///
+    /// ```
+    /// # use publicsuffix::List;
+    /// let list = List::from_toml(
+    ///     r#"
+    ///     icann = ["internal"]
+    ///     private = ["team.internal"]
+    ///     "#,
+    /// )?;
+    /// assert!(list.suffix_is_icann("www.internal"));
+    /// # Ok::<(), publicsuffix::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`List::from_json`].
+    pub fn from_toml(s: &str) -> Result<Self, Error> {
+        let sections =
+            toml::from_str(s).map_err(|error| Error::InvalidConfig(error.to_string()))?;
+        Self::from_sections(sections)
+    }
+
+    fn from_sections(sections: Sections) -> Result<Self, Error> {
+        let mut list = List::new();
+        let mut keys = Interner::default();
+        for (name, typ, rules) in [
+            ("icann", Type::Icann, sections.icann),
+            ("private", Type::Private, sections.private),
+        ] {
+            for (index, rule) in rules.iter().enumerate() {
+                list.add_rule(rule, typ, &mut keys).map_err(|error| {
+                    Error::InvalidConfig(format!("`{}[{}]`: {}", name, index, error))
+                })?;
+            }
+        }
+        if list.is_empty() {
+            return Err(Error::InvalidList);
+        }
+        Ok(list)
+    }
+}
@@ -0,0 +1,155 @@
+//! Comparing lists and updating them in place
+
+use crate::{Error, Interner, List, Type};
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+/// The rules that differ between two lists
+///
+/// Created by [`List::diff`] and [`List::refresh_from_str`]. Rules are
+/// written the way they are in a list, e.g. `*.kawasaki.jp` or
+/// `!city.kawasaki.jp`, and sorted. A rule that only changed sections is
+/// both removed with its old type and added with its new one.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct ListDiff {
+    /// The rules only in the new list
+    pub added: Vec<(String, Type)>,
+    /// The rules only in the old list
+    pub removed: Vec<(String, Type)>,
+}
+
+impl ListDiff {
+    /// Checks to see if the lists have the same rules
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Writes the changes as a list for review
+    ///
+    /// Each section with changes starts with its usual marker. Added rules
+    /// are written as they are, while removed ones are written as
+    /// `// REMOVED: rule` comments, since lists have no way to remove a
+    /// rule. Parsing the patch gives a list of only the added rules.
+    #[must_use]
+    pub fn to_patch(&self) -> String {
+        let mut patch = String::new();
+        for typ in crate::types() {
+            let in_section = |(_, rule_typ): &&(String, Type)| *rule_typ == typ;
+            let added = self.added.iter().filter(in_section).collect::<Vec<_>>();
+            let removed = self.removed.iter().filter(in_section).collect::<Vec<_>>();
+            if added.is_empty() && removed.is_empty() {
+                continue;
+            }
+            let section = match typ {
+                Type::Icann => "ICANN",
+                Type::Private => "PRIVATE",
+            };
+            patch.push_str(&format!("// ===BEGIN {} DOMAINS===\n", section));
+            for (rule, _) in removed {
+                patch.push_str(&format!("// REMOVED: {}\n", rule));
+            }
+            for (rule, _) in added {
+                patch.push_str(rule);
+                patch.push('\n');
+            }
+            patch.push_str(&format!("// ===END {} DOMAINS===\n", section));
+        }
+        patch
+    }
+}
+
+impl List {
+    /// Finds the rules added to and removed from this list in `new`
+    ///
+    /// If either list is restricted to a section, like the list inside an
+    /// `IcannList`, only the rules in that section are compared. With
+    /// `punycode`, the Unicode and ASCII forms of a rule are compared as
+    /// separate rules.
+    #[must_use]
+    pub fn diff(&self, new: &List) -> ListDiff {
+        let typ = self.typ.or(new.typ);
+        diff(&self.rule_set(typ), &new.rule_set(typ))
+    }
+
+    /// Finds which of `domains` get a different suffix from `other`
+    ///
+    /// A domain is affected if its public suffix, the type of that suffix
+    /// or its registrable domain differs between the two lists, e.g. when
+    /// a new rule makes it a suffix of its own or its rule moves from one
+    /// section to the other. This tells whether an update of the list
+    /// changes the cookie scope of given sites. The domains are returned in
+    /// the order they are given.
+    #[must_use]
+    pub fn affected_domains<'a>(&self, other: &List, domains: &[&'a str]) -> Vec<&'a str> {
+        if self.diff(other).is_empty() {
+            return Vec::new();
+        }
+        let outcome = |list: &List, domain| {
+            list.lookup(domain)
+                .map(|found| (found.suffix(), found.typ(), found.registrable_domain()))
+        };
+        domains
+            .iter()
+            .copied()
+            .filter(|domain| outcome(self, domain) != outcome(other, domain))
+            .collect()
+    }
+
+    /// Replaces the rules of this list with the ones in `s`
+    ///
+    /// This parses `s` like [`List::from_str`] and then removes and adds
+    /// only the rules that changed, so refreshing a list with few changes
+    /// leaves most of its tree as it is. The metadata is taken from `s`,
+    /// while the section the list is restricted to and its limits are
+    /// kept. Returns the changes that were made, across both sections.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if `s` is not a valid list, in which case this list
+    /// is left untouched.
+    pub fn refresh_from_str(&mut self, s: &str) -> Result<ListDiff, Error> {
+        let new = List::from_str(s)?;
+        let changes = diff(&self.rule_set(None), &new.rule_set(None));
+        let mut keys = Interner::default();
+        let mut removed = List::new();
+        for (rule, typ) in &changes.removed {
+            removed.append(rule, *typ, &mut keys)?;
+        }
+        self.rules.subtract(&removed.rules, None);
+        for (rule, typ) in &changes.added {
+            self.append(rule, *typ, &mut keys)?;
+        }
+        self.meta = new.meta;
+        Ok(changes)
+    }
+
+    /// The rules of type `typ`, if any, the way they are written in a list
+    fn rule_set(&self, typ: Option<Type>) -> BTreeSet<(String, Type)> {
+        let mut rules = BTreeSet::new();
+        self.walk_with(typ, |labels, info| {
+            let mut rule = labels.to_vec();
+            rule.reverse();
+            let rule = rule.join(".");
+            let rule = if info.is_exception {
+                format!("!{}", rule)
+            } else {
+                rule
+            };
+            rules.insert((rule, info.typ));
+        });
+        rules
+    }
+}
+
+fn diff(old: &BTreeSet<(String, Type)>, new: &BTreeSet<(String, Type)>) -> ListDiff {
+    ListDiff {
+        added: new.difference(old).cloned().collect(),
+        removed: old.difference(new).cloned().collect(),
+    }
+}
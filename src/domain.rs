@@ -0,0 +1,132 @@
+//! Validating domain names
+
+use crate::{Domain, Error, List, Psl};
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+
+/// The maximum length of a domain name, without its trailing dot
+const MAX_DOMAIN_LEN: usize = 253;
+
+/// The maximum length of a label
+const MAX_LABEL_LEN: usize = 63;
+
+impl List {
+    /// Validates `input` as a DNS name and returns its registrable domain
+    ///
+    /// On top of finding the registrable domain, this checks the rules of
+    /// RFC 1035 as relaxed by RFC 1123. Labels must be 1 to 63 characters of
+    /// letters, digits and hyphens that neither start nor end with a hyphen,
+    /// plus underscores unless they are turned off with
+    /// [`List::with_underscores`], the top-level label must not be all digits
+    /// and the whole name must not be longer than 253 characters. Digits-only
+    /// labels are fine elsewhere, so `127.com` is valid, while a dotted IPv4
+    /// address like `127.0.0.1` is reported as one. With `punycode`, these are
+    /// checked against the ASCII form of `input`. A trailing dot is allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InputTooLong` if `input` is over the
+    /// [length limit](List::with_max_input_len), an `Err` describing the
+    /// first rule `input` breaks, or `Error::NoRegistrableDomain` if it is
+    /// valid but is itself a public suffix.
+    pub fn parse_domain<'a>(&self, input: &'a str) -> Result<Domain<'a>, Error> {
+        self.check_input_len(input)?;
+        #[cfg(feature = "punycode")]
+        let ascii =
+            idna::domain_to_ascii(input).map_err(|_| Error::InvalidDomain(input.to_owned()))?;
+        #[cfg(feature = "punycode")]
+        let ascii = ascii.as_str();
+        #[cfg(not(feature = "punycode"))]
+        let ascii = input;
+
+        validate(ascii, self.allow_underscores).map_err(|error| error(input.to_owned()))?;
+        self.domain(input.as_bytes())
+            .ok_or_else(|| Error::NoRegistrableDomain(input.to_owned()))
+    }
+
+    /// Checks that `input` is exactly a registrable domain
+    ///
+    /// This is for inputs where subdomains are not allowed, like domain
+    /// names passed to a registrar, so `example.co.uk` gives
+    /// `("example.co.uk", "co.uk")` while `www.example.co.uk` is an error.
+    /// Unlike [`List::parse_domain`], the syntax of the labels is not
+    /// checked, except that the top-level label must not be all digits.
+    /// A trailing dot is allowed and kept in both slices.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InputTooLong` if `input` is over the
+    /// [length limit](List::with_max_input_len),
+    /// `Error::EmptyDomainLabel` if it is empty or has an empty label,
+    /// `Error::IpAddress` if it is a dotted IPv4 address,
+    /// `Error::NumericTld` if its top-level label is all digits,
+    /// `Error::NoRegistrableDomain` if it is itself a public suffix and
+    /// `Error::HasSubdomains` if it has labels before its registrable
+    /// domain.
+    pub fn validate_registrable<'a>(&self, input: &'a str) -> Result<(&'a str, &'a str), Error> {
+        self.check_input_len(input)?;
+        let name = input.strip_suffix('.').unwrap_or(input);
+        if name.split('.').any(str::is_empty) {
+            return Err(Error::EmptyDomainLabel(input.to_owned()));
+        }
+        check_tld(name).map_err(|error| error(input.to_owned()))?;
+        let found = self
+            .lookup(input)
+            .ok_or_else(|| Error::NoRegistrableDomain(input.to_owned()))?;
+        match found.registrable_domain() {
+            Some(domain) if domain.len() == input.len() => Ok((domain, found.suffix())),
+            Some(_) => Err(Error::HasSubdomains(input.to_owned())),
+            None => Err(Error::NoRegistrableDomain(input.to_owned())),
+        }
+    }
+}
+
+/// Checks the syntax of an ASCII domain name
+fn validate(domain: &str, allow_underscores: bool) -> Result<(), fn(String) -> Error> {
+    let domain = domain.strip_suffix('.').unwrap_or(domain);
+    if domain.len() > MAX_DOMAIN_LEN {
+        return Err(Error::DomainTooLong);
+    }
+    for label in domain.split('.') {
+        if label.is_empty() {
+            return Err(Error::EmptyDomainLabel);
+        }
+        if label.len() > MAX_LABEL_LEN {
+            return Err(Error::LabelTooLong);
+        }
+        if !label.bytes().all(|byte| {
+            byte.is_ascii_alphanumeric() || byte == b'-' || (byte == b'_' && allow_underscores)
+        }) {
+            return Err(Error::InvalidLabelChar);
+        }
+        if label.starts_with('-') {
+            return Err(Error::LabelStartsWithHyphen);
+        }
+        if label.ends_with('-') {
+            return Err(Error::LabelEndsWithHyphen);
+        }
+    }
+    check_tld(domain)
+}
+
+/// Checks that the top-level label of a domain name is not all digits
+///
+/// A name made of four such labels of up to 255 is an IPv4 address, not a
+/// domain name, so it gets an error of its own.
+fn check_tld(domain: &str) -> Result<(), fn(String) -> Error> {
+    let is_numeric = |label: &str| label.bytes().all(|byte| byte.is_ascii_digit());
+    let tld = domain.rsplit('.').next().unwrap_or_default();
+    if !is_numeric(tld) {
+        return Ok(());
+    }
+    let mut labels = domain.split('.');
+    let is_ipv4 = labels.clone().count() == 4
+        && labels.all(|label| {
+            is_numeric(label) && (1..=3).contains(&label.len()) && label.parse::<u8>().is_ok()
+        });
+    if is_ipv4 {
+        Err(Error::IpAddress)
+    } else {
+        Err(Error::NumericTld)
+    }
+}
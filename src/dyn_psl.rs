@@ -0,0 +1,59 @@
+//! Choosing a list at runtime behind a trait object
+
+use crate::{Info, Psl};
+
+/// A version of [`Psl`] that can be used as a trait object
+///
+/// [`Psl::find`] is generic over the labels it is given, so there can be
+/// no `dyn Psl`. This takes the labels as a trait object instead and is
+/// implemented for every `Psl`, so a `Box<dyn DynPsl>` can hold a
+/// [`List`](crate::List), an [`IcannList`](crate::IcannList), a
+/// [`StaticList`](crate::StaticList) or any other list picked at runtime.
+/// `dyn DynPsl` implements `Psl` in turn, so it has the usual methods.
+///
+/// ```
+/// use publicsuffix::{DynPsl, IcannList, List, Psl};
+///
+/// let list: List = "// BEGIN ICANN DOMAINS\nio\n\
+///     // BEGIN PRIVATE DOMAINS\ngithub.io"
+///     .parse()?;
+/// let icann_only = true;
+/// let psl: Box<dyn DynPsl> = if icann_only {
+///     Box::new(IcannList::from(list))
+/// } else {
+///     Box::new(list)
+/// };
+/// assert_eq!(psl.suffix(b"foo.github.io").unwrap(), "io");
+/// # Ok::<(), publicsuffix::Error>(())
+/// ```
+pub trait DynPsl {
+    /// Finds the suffix of a domain name given its labels, like [`Psl::find`]
+    fn find_dyn(&self, labels: &mut dyn Iterator<Item = &[u8]>) -> Info;
+}
+
+impl<L: Psl> DynPsl for L {
+    #[inline]
+    fn find_dyn(&self, labels: &mut dyn Iterator<Item = &[u8]>) -> Info {
+        self.find(labels)
+    }
+}
+
+impl Psl for dyn DynPsl + '_ {
+    #[inline]
+    fn find<'a, T>(&self, mut labels: T) -> Info
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        self.find_dyn(&mut labels)
+    }
+}
+
+impl Psl for dyn DynPsl + Send + Sync + '_ {
+    #[inline]
+    fn find<'a, T>(&self, mut labels: T) -> Info
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        self.find_dyn(&mut labels)
+    }
+}
@@ -5,27 +5,123 @@ use core::fmt;
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 #[non_exhaustive]
 pub enum Error {
+    DomainTooLong(String),
+    EmptyDomainLabel(String),
     EmptyLabel(String),
     ExceptionAtFirstLabel(String),
+    HasSubdomains(String),
+    InputTooLong {
+        /// The length of the input in bytes
+        len: usize,
+        /// The maximum length allowed
+        max: usize,
+    },
+    InvalidConfig(String),
+    InvalidDomain(String),
+    InvalidLabelChar(String),
     InvalidList,
     InvalidRule(String),
+    InvalidUtf8 {
+        /// The offset of the first byte that is not valid UTF-8
+        valid_up_to: usize,
+    },
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
+    IpAddress(String),
+    LabelEndsWithHyphen(String),
+    LabelStartsWithHyphen(String),
+    LabelTooLong(String),
+    LeadingDot(String),
+    #[deprecated(note = "lists that are not UTF-8 are reported as `Error::InvalidUtf8`")]
     ListNotUtf8Encoded,
+    NoRegistrableDomain(String),
+    NumericTld(String),
+    RuleBeforeSection(String),
+    UnsupportedFormat,
 }
 
 impl fmt::Display for Error {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Error::DomainTooLong(domain) => {
+                write!(f, "domain `{}` is too long to be a DNS name", domain)
+            }
+            Error::EmptyDomainLabel(domain) => {
+                write!(f, "domain `{}` contains an empty label", domain)
+            }
             Error::EmptyLabel(rule) => write!(f, "rule `{}` contains an empty label", rule),
             Error::ExceptionAtFirstLabel(rule) => {
                 write!(f, "`{}`; exceptions only valid at end of rule", rule)
             }
+            Error::HasSubdomains(domain) => write!(
+                f,
+                "domain `{}` has labels before its registrable domain",
+                domain
+            ),
+            Error::InputTooLong { len, max } => write!(
+                f,
+                "input of {} bytes is longer than the maximum of {}",
+                len, max
+            ),
+            Error::InvalidConfig(reason) => write!(f, "invalid list configuration: {}", reason),
+            Error::InvalidDomain(domain) => write!(f, "domain `{}` is invalid", domain),
+            Error::InvalidLabelChar(domain) => write!(
+                f,
+                "domain `{}` contains a character other than letters, digits and hyphens",
+                domain
+            ),
             Error::InvalidList => write!(f, "the provided list is not valid"),
             Error::InvalidRule(rule) => write!(f, "rule `{}` is invalid", rule),
+            Error::InvalidUtf8 { valid_up_to } => write!(
+                f,
+                "the provided list is not UTF8 encoded; invalid byte at offset {}",
+                valid_up_to
+            ),
+            #[cfg(feature = "std")]
+            Error::Io(kind) => write!(f, "failed to read the list: {}", kind),
+            Error::IpAddress(input) => {
+                write!(f, "`{}` is an IP address, not a domain name", input)
+            }
+            Error::LabelEndsWithHyphen(domain) => {
+                write!(f, "domain `{}` has a label ending with a hyphen", domain)
+            }
+            Error::LabelStartsWithHyphen(domain) => {
+                write!(f, "domain `{}` has a label starting with a hyphen", domain)
+            }
+            Error::LabelTooLong(domain) => {
+                write!(
+                    f,
+                    "domain `{}` has a label longer than 63 characters",
+                    domain
+                )
+            }
+            Error::LeadingDot(rule) => write!(f, "rule `{}` starts with a dot", rule),
+            #[allow(deprecated)]
             Error::ListNotUtf8Encoded => write!(f, "the provided list is not UTF8 encoded"),
+            Error::NoRegistrableDomain(domain) => {
+                write!(f, "domain `{}` is a public suffix", domain)
+            }
+            Error::NumericTld(domain) => {
+                write!(f, "the top-level label of `{}` is all digits", domain)
+            }
+            Error::RuleBeforeSection(rule) => write!(
+                f,
+                "rule `{}` comes before the `// ===BEGIN ICANN DOMAINS===` or `// ===BEGIN PRIVATE DOMAINS===` marker",
+                rule
+            ),
+            Error::UnsupportedFormat => write!(f, "the list is not in a supported binary format"),
         }
     }
 }
 
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error.kind())
+    }
+}
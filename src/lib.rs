@@ -5,54 +5,449 @@
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+mod binary;
+mod builder;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "config")]
+mod config;
+mod diff;
+mod domain;
+mod dyn_psl;
 mod error;
+mod lookup;
+mod meta;
+mod owner;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+mod rule;
+mod static_list;
+#[cfg(feature = "testing")]
+pub mod testing;
 
-#[cfg(feature = "anycase")]
 use alloc::borrow::Cow;
 use alloc::borrow::ToOwned;
 #[cfg(not(any(feature = "hashbrown", feature = "punycode", feature = "std")))]
 use alloc::collections::BTreeMap as Map;
-#[cfg(not(feature = "anycase"))]
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
+use core::hint::black_box;
+use core::iter::{Chain, Copied, Flatten, FromIterator};
+use core::slice;
 use core::str::{from_utf8, FromStr};
 #[cfg(feature = "hashbrown")]
 use hashbrown::HashMap as Map;
 #[cfg(all(not(feature = "hashbrown"), any(feature = "punycode", feature = "std")))]
 use std::collections::HashMap as Map;
+#[cfg(feature = "std")]
+use std::{io::BufRead, net::IpAddr, path::Path};
 #[cfg(feature = "anycase")]
 use unicase::UniCase;
+#[cfg(feature = "unicode")]
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
 
+pub use builder::ListBuilder;
+#[cfg(feature = "cache")]
+pub use cache::CachedList;
+pub use diff::ListDiff;
+pub use dyn_psl::DynPsl;
 pub use error::Error;
+pub use lookup::{Candidate, Match, MatchPolicy, MatchesIter, Outcome, OwnedDomain, RuleKind};
+pub use meta::ListMeta;
 pub use psl_types::{Domain, Info, List as Psl, Suffix, Type};
+pub use rule::RuleInfo;
+pub use static_list::StaticList;
 
 /// The official URL of the list
 pub const LIST_URL: &str = "https://publicsuffix.org/list/public_suffix_list.dat";
 
+/// All the types of suffixes, in the order their sections appear in the list
+#[inline]
+#[must_use]
+pub const fn types() -> [Type; 2] {
+    [Type::Icann, Type::Private]
+}
+
+/// The labels of `host` from the TLD down, as [`Psl::find`] expects them
+///
+/// A trailing dot is ignored, so `www.example.com.` gives `com`, `example`
+/// and `www`. Empty labels are yielded as is, so an empty host gives a
+/// single empty label, which has no suffix. Nothing is allocated.
+#[inline]
+pub fn labels(host: &str) -> impl Iterator<Item = &str> {
+    host.strip_suffix('.').unwrap_or(host).rsplit('.')
+}
+
+/// The labels of `host` as bytes, like [`labels`]
+///
+/// This is for names that are not guaranteed to be UTF-8, like the ones
+/// passed to [`Psl::suffix`].
+#[inline]
+pub fn labels_bytes(host: &[u8]) -> impl Iterator<Item = &[u8]> {
+    host.strip_suffix(b".")
+        .unwrap_or(host)
+        .rsplit(|x| *x == b'.')
+}
+
 #[cfg(not(feature = "anycase"))]
-type Children = Map<Vec<u8>, Node>;
+type Key = Arc<[u8]>;
 
 #[cfg(feature = "anycase")]
-type Children = Map<UniCase<Cow<'static, str>>, Node>;
+type Key = UniCase<Cow<'static, str>>;
+
+type Children = Map<Key, Node>;
+
+/// The label a key was created from
+#[cfg(not(feature = "anycase"))]
+#[inline]
+fn label(key: &Key) -> &str {
+    from_utf8(key).unwrap_or_default()
+}
+
+/// The label a key was created from
+#[cfg(feature = "anycase")]
+#[inline]
+fn label<'a>(key: &'a UniCase<Cow<'_, str>>) -> &'a str {
+    key.as_ref()
+}
+
+/// `label` with its ASCII letters lowercased
+///
+/// Labels of up to 64 bytes are lowercased into `buf`, so lookups don't
+/// allocate. Other bytes are left as they are.
+#[cfg(all(feature = "ascii-case", not(feature = "anycase")))]
+#[inline]
+fn fold_case<'a>(label: &'a [u8], buf: &'a mut [u8; 64]) -> Cow<'a, [u8]> {
+    if !label.iter().any(u8::is_ascii_uppercase) {
+        return Cow::Borrowed(label);
+    }
+    match buf.get_mut(..label.len()) {
+        Some(folded) => {
+            folded.copy_from_slice(label);
+            folded.make_ascii_lowercase();
+            Cow::Borrowed(folded)
+        }
+        None => Cow::Owned(label.to_ascii_lowercase()),
+    }
+}
+
+/// The NFC form of `s`, if it isn't already in that form
+#[cfg(feature = "unicode")]
+#[inline]
+fn nfc(s: &str) -> Option<String> {
+    if is_nfc_quick(s.chars()) == IsNormalized::Yes {
+        None
+    } else {
+        Some(s.nfc().collect())
+    }
+}
+
+/// A key for looking up a label that was normalized on the fly
+#[cfg(all(feature = "unicode", not(feature = "anycase")))]
+#[inline]
+fn normalized_key(label: String) -> Key {
+    #[cfg(feature = "ascii-case")]
+    let label = label.to_ascii_lowercase();
+    Key::from(label.into_bytes())
+}
+
+/// A key for looking up a label that was normalized on the fly
+#[cfg(all(feature = "unicode", feature = "anycase"))]
+#[inline]
+fn normalized_key(label: String) -> Key {
+    UniCase::new(Cow::Owned(label))
+}
 
 const WILDCARD: &str = "*";
 
+/// The default limit on the length of names looked up by string, as in DNS
+const MAX_INPUT_LEN: usize = 253;
+
+/// Special-use domain names reserved by RFC 6761 and RFC 6762
+const SPECIAL_USE: &[&str] = &["example", "invalid", "local", "localhost", "test"];
+
+/// The most TLDs suggested for an unknown one
+const MAX_TLD_SUGGESTIONS: usize = 5;
+
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 struct Node {
     children: Children,
     leaf: Option<Leaf>,
+    /// Whether one of the children is the `*` label, so lookups that miss
+    /// every other child only hash it again when it is worth it
+    has_wildcard: bool,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 struct Leaf {
     is_exception: bool,
     typ: Type,
+    /// The index of the source the rule came from, if it was recorded
+    #[cfg(feature = "sources")]
+    source: Option<usize>,
+}
+
+impl Node {
+    /// The child node for `key`, created if it isn't there yet
+    #[inline]
+    fn child_mut(&mut self, key: Key) -> &mut Node {
+        self.has_wildcard |= label(&key) == WILDCARD;
+        self.children.entry(key).or_default()
+    }
+
+    /// The child node for the `*` label, if any
+    #[inline]
+    fn wildcard(&self) -> Option<&Node> {
+        if !self.has_wildcard {
+            return None;
+        }
+        #[cfg(not(feature = "anycase"))]
+        let node = self.children.get(WILDCARD.as_bytes());
+        #[cfg(feature = "anycase")]
+        let node = self.children.get(&UniCase::new(Cow::from(WILDCARD)));
+        node
+    }
+
+    /// The key and the child node for the bytes of `label`, if any
+    #[cfg(not(feature = "anycase"))]
+    #[inline]
+    fn get(&self, label: &[u8]) -> Option<(&Key, &Node)> {
+        #[cfg(feature = "ascii-case")]
+        let mut buf = [0; 64];
+        #[cfg(feature = "ascii-case")]
+        let label = &*fold_case(label, &mut buf);
+        self.children.get_key_value(label)
+    }
+
+    /// The child node for `label`, if any
+    #[inline]
+    fn child<'a>(&'a self, label: &'a str) -> Option<&'a Node> {
+        #[cfg(feature = "unicode")]
+        {
+            if let Some(label) = nfc(label) {
+                return self.children.get(&normalized_key(label));
+            }
+        }
+        #[cfg(not(feature = "anycase"))]
+        let node = self.get(label.as_bytes()).map(|(_, node)| node);
+        #[cfg(feature = "anycase")]
+        let node = self.children.get(&UniCase::new(Cow::from(label)));
+        node
+    }
+
+    /// The child node for a label of a domain name given as bytes, if any
+    ///
+    /// Labels that are not valid UTF-8 can't match any rule so they are
+    /// treated like any other unknown label.
+    #[inline]
+    fn child_bytes<'a>(&'a self, label: &'a [u8]) -> Option<&'a Node> {
+        #[cfg(not(any(feature = "anycase", feature = "unicode")))]
+        let node = self.get(label).map(|(_, node)| node);
+        #[cfg(any(feature = "anycase", feature = "unicode"))]
+        let node = from_utf8(label).ok().and_then(|label| self.child(label));
+        node
+    }
+
+    /// The label as stored in the list and the child node for `label`, if any
+    #[inline]
+    fn entry<'a>(&'a self, label: &'a str) -> Option<(&'a str, &'a Node)> {
+        #[cfg(feature = "unicode")]
+        {
+            if let Some(label) = nfc(label) {
+                return self
+                    .children
+                    .get_key_value(&normalized_key(label))
+                    .map(|(key, node)| (self::label(key), node));
+            }
+        }
+        #[cfg(not(feature = "anycase"))]
+        let entry = self.get(label.as_bytes());
+        #[cfg(feature = "anycase")]
+        let entry = self.children.get_key_value(&UniCase::new(Cow::from(label)));
+        entry.map(|(key, node)| (self::label(key), node))
+    }
+
+    /// The labels of the first rule matching `labels` whose leaf is `is_leaf`
+    ///
+    /// Labels are matched by themselves before `*`, in the order lookups
+    /// prefer rules in, and both are given from the top-level label.
+    fn rule_path<'a, F: Fn(Leaf) -> bool>(
+        &'a self,
+        labels: &[&'a str],
+        is_leaf: &F,
+    ) -> Option<Vec<&'a str>> {
+        let (label, rest) = match labels.split_first() {
+            Some(split) => split,
+            None => return self.leaf.filter(|leaf| is_leaf(*leaf)).map(|_| Vec::new()),
+        };
+        let wildcard = self.wildcard().map(|node| (WILDCARD, node));
+        self.entry(label)
+            .into_iter()
+            .chain(wildcard.filter(|_| *label != WILDCARD))
+            .find_map(|(key, node)| {
+                let mut path = node.rule_path(rest, is_leaf)?;
+                path.push(key);
+                Some(path)
+            })
+    }
+
+    /// Whether this node neither holds a rule nor leads to one
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.leaf.is_none() && self.children.is_empty()
+    }
+
+    /// Whether this node or any node below it holds a rule of type `typ`
+    fn has_rule_of(&self, typ: Type) -> bool {
+        matches!(self.leaf, Some(leaf) if leaf.typ == typ)
+            || self.children.values().any(|child| child.has_rule_of(typ))
+    }
+
+    /// The number of rules of type `typ`, or of all types, at or below a node
+    fn rule_count(&self, typ: Option<Type>) -> usize {
+        let here = matches!(self.leaf, Some(leaf) if typ.is_none() || typ == Some(leaf.typ));
+        usize::from(here)
+            + self
+                .children
+                .values()
+                .map(|child| child.rule_count(typ))
+                .sum::<usize>()
+    }
+
+    /// Pushes the children of this node onto `stack` in canonical order
+    ///
+    /// The order doesn't depend on the map backing the tree. Children are
+    /// sorted by label bytes, in reverse so that popping them off the
+    /// stack yields them in ascending order.
+    #[inline]
+    fn push_sorted_children<'a>(
+        &'a self,
+        depth: usize,
+        stack: &mut Vec<(usize, &'a str, &'a Node)>,
+    ) {
+        let start = stack.len();
+        stack.extend(
+            self.children
+                .iter()
+                .map(|(key, child)| (depth, label(key), child)),
+        );
+        stack[start..].sort_unstable_by(|a, b| b.1.cmp(a.1));
+    }
+
+    /// Replaces the type of every rule at or below this node with `f` of it
+    fn map_types<F: Fn(Type) -> Type>(&mut self, f: &F) {
+        if let Some(leaf) = &mut self.leaf {
+            leaf.typ = f(leaf.typ);
+        }
+        for child in self.children.values_mut() {
+            child.map_types(f);
+        }
+    }
+
+    /// Removes the rules found in `other`, pruning nodes left empty
+    ///
+    /// If `typ` is set, only rules of that type are considered.
+    fn subtract(&mut self, other: &Node, typ: Option<Type>) {
+        if let (Some(leaf), Some(other_leaf)) = (self.leaf, other.leaf) {
+            if leaf.is_exception == other_leaf.is_exception
+                && (typ.is_none() || typ == Some(other_leaf.typ))
+            {
+                self.leaf = None;
+            }
+        }
+        for (key, other_child) in &other.children {
+            if let Some(child) = self.children.get_mut(key) {
+                child.subtract(other_child, typ);
+                if child.is_empty() {
+                    self.children.remove(key);
+                    self.has_wildcard &= label(key) != WILDCARD;
+                }
+            }
+        }
+    }
+
+    /// Removes the nodes below this one that don't hold or lead to a rule
+    fn dedup(&mut self) {
+        self.children.retain(|_, child| {
+            child.dedup();
+            !child.is_empty()
+        });
+        self.has_wildcard &= self.wildcard().is_some();
+    }
+}
+
+/// Hands out the keys of the tree while building it
+///
+/// Labels like `com`, `co` and `org` appear under many different nodes.
+/// Interning them makes all those keys share a single allocation.
+#[derive(Debug, Default)]
+struct Interner {
+    #[cfg(not(feature = "anycase"))]
+    labels: Map<Key, ()>,
+    /// The source the rules being added come from, recorded in their leaves
+    #[cfg(feature = "sources")]
+    source: Option<usize>,
+}
+
+impl Interner {
+    #[cfg(not(feature = "anycase"))]
+    #[inline]
+    fn key(&mut self, label: &str) -> Key {
+        let label = label.as_bytes();
+        #[cfg(feature = "ascii-case")]
+        let mut buf = [0; 64];
+        #[cfg(feature = "ascii-case")]
+        let label = &*fold_case(label, &mut buf);
+        if let Some((key, _)) = self.labels.get_key_value(label) {
+            return key.clone();
+        }
+        let key = Key::from(label);
+        self.labels.insert(key.clone(), ());
+        key
+    }
+
+    #[cfg(feature = "anycase")]
+    #[inline]
+    fn key(&mut self, label: &str) -> Key {
+        UniCase::new(Cow::from(label.to_owned()))
+    }
 }
 
 /// A dynamic public suffix list
-#[derive(Debug, Clone, Default, Eq, PartialEq)]
+///
+/// Without the `anycase` or `ascii-case` features, labels are matched byte
+/// for byte, so `Example.com` and `example.com` are different names and
+/// rules are case sensitive. The official list is all lowercase, so names
+/// need to be lowercased before they are looked up. With `anycase`, labels
+/// are matched case insensitively instead, and with `ascii-case` only
+/// their ASCII letters are.
+///
+/// References to a list implement [`Psl`] too, so `&list` can be passed
+/// to functions generic over `P: Psl`. `Psl` has generic methods, so it
+/// can't be used as a trait object like `&dyn Psl`.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct List {
     rules: Node,
     typ: Option<Type>,
+    meta: ListMeta,
+    max_input_len: usize,
+    allow_underscores: bool,
+}
+
+impl Default for List {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            rules: Node::default(),
+            typ: None,
+            meta: ListMeta::default(),
+            max_input_len: MAX_INPUT_LEN,
+            allow_underscores: true,
+        }
+    }
 }
 
 impl List {
@@ -72,10 +467,208 @@ impl List {
     #[inline]
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
         from_utf8(bytes)
-            .map_err(|_| Error::ListNotUtf8Encoded)?
+            .map_err(|error| Error::InvalidUtf8 {
+                valid_up_to: error.valid_up_to(),
+            })?
             .parse()
     }
 
+    /// Creates a new list from several lists, like the official one and yours
+    ///
+    /// The sources are parsed one after the other, each with its own
+    /// sections, as if they were a single list. A rule in more than one
+    /// source gets the type it has in the last one. The metadata comes from
+    /// the headers of the sources, with later ones overriding earlier ones.
+    /// With the `sources` feature, [`List::rule_source`] tells which
+    /// source each rule came from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the format of any source is invalid or if they
+    /// have no rules at all.
+    pub fn from_sources(sources: &[&str]) -> Result<Self, Error> {
+        let mut parser = Parser::default();
+        for source in sources {
+            parser.next_source();
+            for line in source.lines() {
+                parser.line(line)?;
+            }
+        }
+        parser.finish()
+    }
+
+    /// Creates a new list from a file
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the file could not be read, if it is
+    /// not UTF-8 encoded or if its format is invalid.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Creates a new list from a reader, parsing it one line at a time
+    ///
+    /// Only the current line is held in memory, so this can parse a list
+    /// while it is still being downloaded or decompressed. For example, to
+    /// parse a gzipped HTTP response body as it arrives, wrap the body in a
+    /// streaming decoder (like `flate2::read::GzDecoder`) and that in a
+    /// `std::io::BufReader`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if reading fails, if the list is not UTF-8
+    /// encoded or if its format is invalid.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn from_reader<R: BufRead>(mut reader: R) -> Result<Self, Error> {
+        let mut parser = Parser::default();
+        let mut buf = Vec::new();
+        let mut offset = 0;
+        loop {
+            buf.clear();
+            if reader.read_until(b'\n', &mut buf)? == 0 {
+                break;
+            }
+            let line = from_utf8(&buf).map_err(|error| Error::InvalidUtf8 {
+                valid_up_to: offset + error.valid_up_to(),
+            })?;
+            offset += buf.len();
+            parser.line(line.trim_end_matches(&['\n', '\r'][..]))?;
+        }
+        parser.finish()
+    }
+
+    /// Creates a new list from a gzip compressed copy of it
+    ///
+    /// This is meant for lists embedded with `include_bytes!`, which take
+    /// about a third of the space of the plain text list. `bytes` must be
+    /// in the gzip format (RFC 1952), as produced by running
+    /// `gzip -9 -k public_suffix_list.dat`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if `bytes` are not valid gzip data, if the list is
+    /// not UTF-8 encoded or if its format is invalid.
+    #[cfg(feature = "gzip")]
+    #[inline]
+    pub fn from_compressed(bytes: &[u8]) -> Result<Self, Error> {
+        Self::from_reader(std::io::BufReader::new(flate2::read::GzDecoder::new(bytes)))
+    }
+
+    /// Creates a new list with each of `tlds` as an ICANN rule
+    ///
+    /// This is a fallback for when the full list isn't available, built
+    /// from the TLDs IANA delegates in the root zone, as published in
+    /// `https://data.iana.org/TLD/tlds-alpha-by-domain.txt`. The lines of
+    /// that file can be passed as they are: TLDs are lowercased, and empty
+    /// lines and `#` comments are skipped, as are TLDs that aren't valid
+    /// rules.
+    ///
+    /// The list only knows single-label suffixes, so `example.co.uk` gets
+    /// `uk` as its suffix instead of `co.uk`. Adding the special-use
+    /// domains with [`List::with_special_use`] on top makes a reasonable
+    /// baseline without the full list.
+    #[must_use]
+    pub fn from_iana_tlds(tlds: &[&str]) -> Self {
+        let mut list = Self::new();
+        let mut keys = Interner::default();
+        for tld in tlds.iter().map(|tld| tld.trim()) {
+            if tld.is_empty() || tld.starts_with('#') {
+                continue;
+            }
+            let _ = list.add_rule(&tld.to_ascii_lowercase(), Type::Icann, &mut keys);
+        }
+        list
+    }
+
+    /// Adds the special-use domains as ICANN rules
+    ///
+    /// These are `example`, `invalid`, `localhost` and `test` from RFC 6761
+    /// and `local` from RFC 6762. They are not part of the official list but
+    /// many applications treat them as public suffixes.
+    #[inline]
+    #[must_use]
+    pub fn with_special_use(mut self) -> Self {
+        let mut keys = Interner::default();
+        for rule in SPECIAL_USE {
+            // these are all valid, single label rules
+            let _ = self.append(rule, Type::Icann, &mut keys);
+        }
+        self
+    }
+
+    /// Sets the maximum length of the names looked up by string
+    ///
+    /// Longer names are rejected before they are split into labels, which
+    /// keeps untrusted input from making lookups do much work. The limit
+    /// is in bytes and doesn't count a trailing dot. It defaults to 253,
+    /// the longest name DNS allows. Unicode names take more bytes than
+    /// their ASCII form, so raise the limit if long ones are expected.
+    ///
+    /// This applies to the methods of `List` that take a `&str` or a
+    /// whole host name, like [`List::lookup`] and [`List::split`], but not
+    /// to the [`Psl`] methods, which work on labels.
+    #[inline]
+    #[must_use]
+    pub fn with_max_input_len(mut self, max_input_len: usize) -> Self {
+        self.max_input_len = max_input_len;
+        self
+    }
+
+    /// The maximum length of the names looked up by string
+    ///
+    /// See [`List::with_max_input_len`].
+    #[inline]
+    #[must_use]
+    pub const fn max_input_len(&self) -> usize {
+        self.max_input_len
+    }
+
+    /// Sets whether [`List::parse_domain`] accepts labels with underscores
+    ///
+    /// Underscores are not allowed in host names, but DNS names like
+    /// `_tcp.example.com` for SRV or TXT records use them, so they are
+    /// accepted by default. Finding suffixes never looks at them either
+    /// way; only validating a name as a host name can reject them.
+    #[inline]
+    #[must_use]
+    pub fn with_underscores(mut self, allow: bool) -> Self {
+        self.allow_underscores = allow;
+        self
+    }
+
+    /// Whether [`List::parse_domain`] accepts labels with underscores
+    ///
+    /// See [`List::with_underscores`].
+    #[inline]
+    #[must_use]
+    pub const fn allows_underscores(&self) -> bool {
+        self.allow_underscores
+    }
+
+    /// Checks whether `name` is over the length limit
+    #[inline]
+    fn is_too_long(&self, name: &[u8]) -> bool {
+        name.len() > self.max_input_len
+            && (name.len() - 1 > self.max_input_len || !name.ends_with(b"."))
+    }
+
+    /// Returns `Error::InputTooLong` if `input` is over the length limit
+    #[inline]
+    fn check_input_len(&self, input: &str) -> Result<(), Error> {
+        if self.is_too_long(input.as_bytes()) {
+            return Err(Error::InputTooLong {
+                len: input.len(),
+                max: self.max_input_len,
+            });
+        }
+        Ok(())
+    }
+
     /// Checks to see if the list is empty, ignoring the wildcard rule
     #[inline]
     #[must_use]
@@ -83,115 +676,1470 @@ impl List {
         self.rules.children.is_empty()
     }
 
+    /// Metadata found in the header comments of the parsed list
     #[inline]
-    fn append(&mut self, mut rule: &str, typ: Type) -> Result<(), Error> {
-        let mut is_exception = false;
-        if rule.starts_with('!') {
-            if !rule.contains('.') {
-                return Err(Error::ExceptionAtFirstLabel(rule.to_owned()));
-            }
-            is_exception = true;
-            rule = &rule[1..];
+    #[must_use]
+    pub const fn meta(&self) -> &ListMeta {
+        &self.meta
+    }
+
+    /// Checks whether all the rules in the list are ICANN ones
+    ///
+    /// An empty list has no rules of either type, so this returns `false`.
+    #[inline]
+    #[must_use]
+    pub fn is_icann_only(&self) -> bool {
+        self.has_rules_of(Type::Icann) && !self.has_rules_of(Type::Private)
+    }
+
+    /// Checks whether all the rules in the list are private ones
+    ///
+    /// An empty list has no rules of either type, so this returns `false`.
+    #[inline]
+    #[must_use]
+    pub fn is_private_only(&self) -> bool {
+        self.has_rules_of(Type::Private) && !self.has_rules_of(Type::Icann)
+    }
+
+    #[inline]
+    fn has_rules_of(&self, typ: Type) -> bool {
+        (self.typ.is_none() || self.typ == Some(typ)) && self.rules.has_rule_of(typ)
+    }
+
+    /// Checks whether `tld` is a top-level label of any rule in the list
+    ///
+    /// This is a single lookup, unlike finding the suffix of a name.
+    /// With `anycase`, `tld` is matched case insensitively.
+    #[inline]
+    #[must_use]
+    pub fn is_known_tld(&self, tld: &str) -> bool {
+        self.rules.child(tld).is_some()
+    }
+
+    /// Iterates over the top-level labels of the rules in the list
+    ///
+    /// These are the labels [`List::is_known_tld`] knows about, in byte
+    /// order. A top-level `*` rule is not a label, so it is skipped.
+    /// Only the first level of the tree is visited, which is much
+    /// cheaper than walking all the rules.
+    #[inline]
+    pub fn iter_tlds(&self) -> impl Iterator<Item = &str> {
+        let mut tlds = self
+            .rules
+            .children
+            .keys()
+            .map(label)
+            .filter(|tld| *tld != WILDCARD)
+            .collect::<Vec<_>>();
+        tlds.sort_unstable();
+        tlds.into_iter()
+    }
+
+    /// Suggests known TLDs close to an unknown one, e.g. `com` for `con`
+    ///
+    /// This is meant for helpful errors in forms, once [`List::is_known_tld`]
+    /// says no. TLDs are compared by edit distance, ignoring ASCII case, and
+    /// suggested if they are at most 1 edit away, or 2 for TLDs longer than
+    /// 3 characters. The closest come first, then in byte order, and no
+    /// more than 5 are suggested. A known TLD gets no suggestions.
+    #[must_use]
+    pub fn suggest_tld(&self, tld: &str) -> Vec<String> {
+        if tld.is_empty() || self.is_known_tld(tld) {
+            return Vec::new();
         }
+        let max = if tld.chars().count() > 3 { 2 } else { 1 };
+        let mut suggestions = self
+            .iter_tlds()
+            .filter_map(|known| Some((edit_distance(tld, known, max)?, known)))
+            .collect::<Vec<_>>();
+        suggestions.sort_unstable();
+        suggestions
+            .into_iter()
+            .take(MAX_TLD_SUGGESTIONS)
+            .map(|(_, known)| known.to_owned())
+            .collect()
+    }
 
-        let mut current = &mut self.rules;
+    /// Counts the rules under each top-level label
+    ///
+    /// The count of a TLD includes the rule for the TLD itself, if any.
+    /// TLDs are sorted by count, largest first, then by label, and the
+    /// ones without rules of the list's type are left out. With
+    /// `punycode`, rules stored in both their Unicode and ASCII forms are
+    /// counted under each form's TLD.
+    #[must_use]
+    pub fn tld_histogram(&self) -> Vec<(String, usize)> {
+        let mut histogram = self
+            .rules
+            .children
+            .iter()
+            .map(|(key, node)| (label(key).to_owned(), node.rule_count(self.typ)))
+            .filter(|(_, count)| *count > 0)
+            .collect::<Vec<_>>();
+        histogram.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        histogram
+    }
+
+    /// Returns the attributes of `rule` if it is stored in the list
+    ///
+    /// `rule` is written the same way as in the list, e.g. `*.kawasaki.jp`
+    /// or `!city.kawasaki.jp`, and must match a stored rule exactly. Unlike
+    /// finding a suffix, no wildcard is applied, so `foo.kawasaki.jp`
+    /// isn't found even though `*.kawasaki.jp` is in the list.
+    #[must_use]
+    pub fn rule_info(&self, rule: &str) -> Option<RuleInfo> {
+        self.leaf_of(rule).map(RuleInfo::from)
+    }
+
+    /// Returns the index of the source `rule` came from
+    ///
+    /// This is its index among the sources given to
+    /// [`List::from_sources`]. `rule` is written the same way as for
+    /// [`List::rule_info`]. Returns `None` if the rule isn't in the list
+    /// or was added in any other way, including by loading a saved list,
+    /// since sources aren't saved.
+    #[cfg(feature = "sources")]
+    #[must_use]
+    pub fn rule_source(&self, rule: &str) -> Option<usize> {
+        self.leaf_of(rule)?.source
+    }
+
+    /// The leaf of `rule` as written in the list, if it's stored in it
+    fn leaf_of(&self, rule: &str) -> Option<Leaf> {
+        let (is_exception, rule) = match rule.strip_prefix('!') {
+            Some(rule) => (true, rule),
+            None => (false, rule),
+        };
+        let mut node = &self.rules;
         for label in rule.rsplit('.') {
-            if label.is_empty() {
-                return Err(Error::EmptyLabel(rule.to_owned()));
+            node = node.child(label)?;
+        }
+        node.leaf
+            .filter(|leaf| leaf.is_exception == is_exception)
+            .filter(|leaf| self.typ.is_none() || self.typ == Some(leaf.typ))
+    }
+
+    /// Calls `visitor` for each rule in the list
+    ///
+    /// The visitor gets the labels of the rule in the order they are
+    /// matched, i.e. starting from the TLD, so `!city.kawasaki.jp` is
+    /// visited as `["jp", "kawasaki", "city"]`. Exception rules are
+    /// visited without their leading `!`; [`RuleInfo::is_exception`] is
+    /// set for them instead. The wildcard label `*` is visited as is.
+    ///
+    /// Rules are visited depth first with sibling labels in byte order, so
+    /// the order is the same from one walk to the next and doesn't depend
+    /// on the map backing the tree. The labels are borrowed from a single
+    /// buffer that is reused across the whole walk.
+    #[inline]
+    pub fn walk<F>(&self, visitor: F)
+    where
+        F: FnMut(&[&str], RuleInfo),
+    {
+        self.walk_with(self.typ, visitor);
+    }
+
+    /// Like `walk` but only visiting rules of type `typ`, if any
+    fn walk_with<F>(&self, typ: Option<Type>, mut visitor: F)
+    where
+        F: FnMut(&[&str], RuleInfo),
+    {
+        let mut path = Vec::new();
+        let mut stack = Vec::new();
+        self.rules.push_sorted_children(0, &mut stack);
+        while let Some((depth, label, node)) = stack.pop() {
+            path.truncate(depth);
+            path.push(label);
+            if let Some(leaf) = node.leaf {
+                if typ.is_none() || typ == Some(leaf.typ) {
+                    visitor(&path, leaf.into());
+                }
+            }
+            node.push_sorted_children(depth + 1, &mut stack);
+        }
+    }
+
+    /// Iterates over the exception rules in the list
+    ///
+    /// The rules are written the way they are in the list, leading `!`
+    /// included, e.g. `!city.kawasaki.jp`. They come in the same order as
+    /// in [`List::walk`].
+    pub fn exceptions(&self) -> impl Iterator<Item = String> {
+        let mut exceptions = Vec::new();
+        self.walk(|labels, info| {
+            if info.is_exception {
+                let mut rule = labels.to_vec();
+                rule.reverse();
+                exceptions.push(format!("!{}", rule.join(".")));
+            }
+        });
+        exceptions.into_iter()
+    }
+
+    /// Drops the rules of every top-level label that is not in `tlds`
+    ///
+    /// The labels in `tlds` are matched the same way as in
+    /// [`List::is_known_tld`]. Names under a dropped TLD are then only
+    /// matched by the implicit `*` rule.
+    pub fn retain_tlds(&mut self, tlds: &[&str]) {
+        let keep = tlds
+            .iter()
+            .filter_map(|tld| self.rules.entry(tld))
+            .map(|(tld, _)| tld.to_owned())
+            .collect::<Vec<_>>();
+        self.rules
+            .children
+            .retain(|key, _| keep.iter().any(|tld| tld == label(key)));
+        self.rules.has_wildcard &= keep.iter().any(|tld| tld == WILDCARD);
+    }
+
+    /// Copies the rules under `tld` into a list of their own
+    ///
+    /// Only the rules under `tld` are cloned. Names under `tld` get the
+    /// same suffixes from the new list as from this one, while names under
+    /// any other TLD are only matched by the implicit `*` rule, as if the
+    /// TLD wasn't in the list. With `punycode`, a TLD and its ASCII form
+    /// are separate labels, so each has to be extracted on its own.
+    /// Returns `None` if `tld` isn't a top-level label of any rule.
+    #[must_use]
+    pub fn extract_subtree(&self, tld: &str) -> Option<List> {
+        let (tld, node) = self.rules.entry(tld)?;
+        let mut list = List {
+            typ: self.typ,
+            meta: self.meta.clone(),
+            max_input_len: self.max_input_len,
+            allow_underscores: self.allow_underscores,
+            ..List::new()
+        };
+        *list.rules.child_mut(Interner::default().key(tld)) = node.clone();
+        Some(list)
+    }
+
+    /// Reads every node of the tree, pulling it into the CPU caches
+    ///
+    /// Calling this right after loading a list takes the cache misses off
+    /// the first lookups. It is only a hint and has no effect on the result
+    /// of any query.
+    #[inline]
+    pub fn warm(&self) {
+        let mut stack = Vec::new();
+        stack.push(&self.rules);
+        while let Some(node) = stack.pop() {
+            black_box(node.leaf);
+            for (key, child) in &node.children {
+                black_box(label(key));
+                stack.push(child);
+            }
+        }
+    }
+
+    /// Finds the rules that a wildcard rule already covers
+    ///
+    /// A rule like `foo.uk` adds nothing if `*.uk` is in the list with the
+    /// same type, since the wildcard already makes `foo.uk` a suffix. Only
+    /// rules without rules below them are reported, because a rule that
+    /// leads to longer ones still decides the suffix of names under it
+    /// that none of those match. Exceptions are never reported and neither
+    /// are rules covered by a top-level `*`, which isn't applied to unknown
+    /// TLDs. The rules are returned the way they are written in the list.
+    #[must_use]
+    pub fn find_shadowed(&self) -> Vec<String> {
+        self.lint_rules(|parents, label, node, leaf| {
+            let parent = parents[parents.len() - 1];
+            match parent.wildcard().and_then(|wildcard| wildcard.leaf) {
+                Some(wildcard) => {
+                    parents.len() > 1
+                        && label != WILDCARD
+                        && node.children.is_empty()
+                        && !leaf.is_exception
+                        && !wildcard.is_exception
+                        && leaf.typ == wildcard.typ
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// Finds the rules that tie with a wildcard rule of the other type
+    ///
+    /// With `*.foo` in the `ICANN` section and `bar.foo` in the private
+    /// one, both rules match `a.bar.foo` at the same label. Ties like this
+    /// are broken the same way every time: a label in the list always
+    /// beats `*`, so `bar.foo` decides the suffix and it is a private one.
+    /// This reports such rules, e.g. `bar.foo`, the way they are written
+    /// in the list, so list maintainers can check that this is what they
+    /// meant. Exceptions never tie, since they end the match.
+    ///
+    /// A rule can't be in both sections with the same labels: adding it
+    /// again replaces its type, so in a parsed list the private section
+    /// wins.
+    #[must_use]
+    pub fn find_ambiguities(&self) -> Vec<String> {
+        self.lint_rules(|parents, label, _, leaf| {
+            let wildcard = parents[parents.len() - 1]
+                .wildcard()
+                .and_then(|wildcard| wildcard.leaf)
+                .filter(|leaf| self.typ.is_none() || self.typ == Some(leaf.typ));
+            match wildcard {
+                Some(wildcard) => {
+                    parents.len() > 1
+                        && label != WILDCARD
+                        && !leaf.is_exception
+                        && !wildcard.is_exception
+                        && leaf.typ != wildcard.typ
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// Finds the exception rules that no wildcard rule governs
+    ///
+    /// An exception like `!city.kawasaki.jp` only makes sense below a
+    /// wildcard rule like `*.kawasaki.jp`, which it carves a name out of.
+    /// This reports exceptions with no wildcard rule of the list's type
+    /// directly under any of their ancestors, which is most likely a
+    /// mistake in the list. The rules are returned the way they are
+    /// written in the list, leading `!` included.
+    #[must_use]
+    pub fn dangling_exceptions(&self) -> Vec<String> {
+        self.lint_rules(|parents, _, _, leaf| {
+            let is_governed = parents.iter().any(|parent| {
+                matches!(
+                    parent.wildcard().and_then(|wildcard| wildcard.leaf),
+                    Some(wildcard) if !wildcard.is_exception && wildcard.typ == leaf.typ
+                )
+            });
+            leaf.is_exception && !is_governed
+        })
+    }
+
+    /// Finds the rules of the list's type that `lint` picks out
+    ///
+    /// `lint` is given the nodes from the root down to the parent of the
+    /// rule, the last label of the rule, its node and its leaf. The rules
+    /// are returned the way they are written in the list, in the same
+    /// order as in [`List::walk`].
+    fn lint_rules<F>(&self, lint: F) -> Vec<String>
+    where
+        F: Fn(&[&Node], &str, &Node, Leaf) -> bool,
+    {
+        let mut rules = Vec::new();
+        let mut path = Vec::new();
+        let mut parents = Vec::new();
+        let mut stack = Vec::new();
+        self.rules.push_sorted_children(0, &mut stack);
+        parents.push(&self.rules);
+        while let Some((depth, label, node)) = stack.pop() {
+            path.truncate(depth);
+            path.push(label);
+            parents.truncate(depth + 1);
+            if let Some(leaf) = node.leaf {
+                if (self.typ.is_none() || self.typ == Some(leaf.typ))
+                    && lint(&parents, label, node, leaf)
+                {
+                    let mut rule = path.clone();
+                    rule.reverse();
+                    let rule = rule.join(".");
+                    rules.push(if leaf.is_exception {
+                        format!("!{}", rule)
+                    } else {
+                        rule
+                    });
+                }
+            }
+            parents.push(node);
+            node.push_sorted_children(depth + 1, &mut stack);
+        }
+        rules
+    }
+
+    /// Checks that the rules of the list are well-formed
+    ///
+    /// See [`List::validate`] for the checks made.
+    #[inline]
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Checks that the rules of the list are well-formed
+    ///
+    /// Rules added by parsing or inserting always are, but this is a
+    /// safety net for lists put together in other ways. Every label must
+    /// be non-empty and free of dots, every label must lead to a rule and
+    /// exception rules must have more than one label.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` with the first offending rule or path of labels.
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut path = Vec::new();
+        let mut stack = Vec::new();
+        self.rules.push_sorted_children(0, &mut stack);
+        while let Some((depth, label, node)) = stack.pop() {
+            path.truncate(depth);
+            path.push(label);
+            let rule = || {
+                let mut rule = path.clone();
+                rule.reverse();
+                rule.join(".")
+            };
+            if label.is_empty() {
+                return Err(Error::EmptyLabel(rule()));
+            }
+            if label.contains('.') || node.is_empty() {
+                return Err(Error::InvalidRule(rule()));
+            }
+            if matches!(node.leaf, Some(leaf) if leaf.is_exception && depth == 0) {
+                return Err(Error::ExceptionAtFirstLabel(format!("!{}", rule())));
+            }
+            node.push_sorted_children(depth + 1, &mut stack);
+        }
+        Ok(())
+    }
+
+    /// Removes every rule that is also in `other`
+    ///
+    /// Rules are matched by their labels and whether or not they are
+    /// exceptions. If `other` is restricted to a section, like the list
+    /// inside an `IcannList`, only the rules in that section are removed.
+    #[inline]
+    pub fn subtract(&mut self, other: &List) {
+        self.rules.subtract(&other.rules, other.typ);
+    }
+
+    /// Removes the nodes that neither hold a rule nor lead to one
+    ///
+    /// Removing rules through this crate already drops the nodes left
+    /// empty, but lists put together in other ways, e.g. loaded from
+    /// files saved by other tools, may still have some. They match
+    /// nothing, so removing them frees their memory without changing any
+    /// lookup and makes such lists pass [`List::validate`] again, unless
+    /// they are broken in other ways. Calling this again does nothing.
+    pub fn dedup(&mut self) {
+        self.rules.dedup();
+    }
+
+    /// Replaces the type of every rule with `f` of it
+    ///
+    /// This is cheaper than building the list again, e.g. to treat all
+    /// private suffixes as ICANN ones with
+    /// `list.map_types(|_| Type::Icann)`. Exception rules are remapped
+    /// too. The section an [`IcannList`] or a [`PrivateList`] keeps to is
+    /// not, so their rules may then fall outside of it.
+    pub fn map_types<F: Fn(Type) -> Type>(&mut self, f: F) {
+        self.rules.map_types(&f);
+    }
+
+    /// Adds a rule to the list
+    ///
+    /// Returns `Ok(true)` if the rule is new and `Ok(false)` if it replaced
+    /// an existing rule with the same labels, like `HashMap::insert`.
+    ///
+    /// With `punycode`, the rule is also added in its ASCII form. Labels
+    /// with hyphens anywhere, like `a--b`, are accepted as they are. A rule
+    /// that has no ASCII form, e.g. because it has an `xn--` label that
+    /// isn't valid punycode, is only added as written. Lists are parsed
+    /// the same way.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the rule is invalid.
+    #[inline]
+    pub fn insert_checked(&mut self, rule: &str, typ: Type) -> Result<bool, Error> {
+        self.add_rule(rule, typ, &mut Interner::default())
+    }
+
+    /// Adds an ICANN rule to the list
+    ///
+    /// This is [`List::insert_checked`] with `Type::Icann`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the rule is invalid.
+    #[inline]
+    pub fn insert_icann(&mut self, rule: &str) -> Result<bool, Error> {
+        self.insert_checked(rule, Type::Icann)
+    }
+
+    /// Adds a private rule to the list
+    ///
+    /// This is [`List::insert_checked`] with `Type::Private`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the rule is invalid.
+    #[inline]
+    pub fn insert_private(&mut self, rule: &str) -> Result<bool, Error> {
+        self.insert_checked(rule, Type::Private)
+    }
+
+    /// Appends a rule along with its ASCII form when `punycode` is enabled
+    #[inline]
+    fn add_rule(&mut self, rule: &str, typ: Type, keys: &mut Interner) -> Result<bool, Error> {
+        #[cfg(feature = "unicode")]
+        let normalized = nfc(rule);
+        #[cfg(feature = "unicode")]
+        let rule = normalized.as_deref().unwrap_or(rule);
+        let created = self.append(rule, typ, keys)?;
+        // a rule without an ASCII form can still match names written like it,
+        // while ASCII rules are kept as written so they keep their case
+        #[cfg(feature = "punycode")]
+        if !rule.is_ascii() {
+            if let Ok(ascii) = idna::domain_to_ascii(rule) {
+                if ascii != rule {
+                    self.append(&ascii, typ, keys)?;
+                }
+            }
+        }
+        Ok(created)
+    }
+
+    /// Appends a rule, returning whether it is new
+    #[inline]
+    fn append(&mut self, mut rule: &str, typ: Type, keys: &mut Interner) -> Result<bool, Error> {
+        let mut is_exception = false;
+        if rule.starts_with('!') {
+            if !rule.contains('.') {
+                return Err(Error::ExceptionAtFirstLabel(rule.to_owned()));
+            }
+            is_exception = true;
+            rule = &rule[1..];
+        }
+
+        // validate all labels first so a bad rule leaves no empty nodes behind
+        if rule.starts_with('.') {
+            return Err(Error::LeadingDot(rule.to_owned()));
+        }
+        if rule.split('.').any(str::is_empty) {
+            return Err(Error::EmptyLabel(rule.to_owned()));
+        }
+
+        let mut current = &mut self.rules;
+        for label in rule.rsplit('.') {
+            current = current.child_mut(keys.key(label));
+        }
+
+        let leaf = Leaf {
+            is_exception,
+            typ,
+            #[cfg(feature = "sources")]
+            source: keys.source,
+        };
+        Ok(current.leaf.replace(leaf).is_none())
+    }
+}
+
+/// The outcome of walking the rules with a domain name's labels
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Found {
+    info: Info,
+    /// The number of labels in the suffix
+    labels: usize,
+    /// Whether the suffix was determined by an exception rule
+    is_exception: bool,
+    /// Whether the rule behind the suffix was reached through a `*` label
+    is_wildcard: bool,
+    /// Whether no rule matched, leaving the suffix to the implicit `*` rule
+    is_implicit: bool,
+    /// The length of the longest suffix matched by an ICANN rule, if any
+    icann_len: usize,
+    /// The length of the longest suffix matched by a private rule, if any
+    private_len: usize,
+}
+
+impl Found {
+    /// Records a suffix matched by a rule of type `typ`
+    #[inline]
+    fn set_len_of(&mut self, typ: Type, len: usize) {
+        match typ {
+            Type::Icann => self.icann_len = len,
+            Type::Private => self.private_len = len,
+        }
+    }
+}
+
+impl Default for Found {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            info: Info { len: 0, typ: None },
+            labels: 0,
+            is_exception: false,
+            is_wildcard: false,
+            is_implicit: false,
+            icann_len: 0,
+            private_len: 0,
+        }
+    }
+}
+
+impl List {
+    /// Walks the rules, tracking everything we learn about the match
+    ///
+    /// Only rules of type `typ` are matched, or rules of either type if
+    /// it is `None`.
+    #[inline]
+    fn find_match<'a, T>(&self, mut labels: T, typ: Option<Type>) -> Found
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        let mut rules = &self.rules;
+
+        // the first label
+        // it's special because we always need it whether or not
+        // it's in our hash map (because of the implicit wildcard)
+        let mut found = match labels.next() {
+            Some(label) => {
+                let mut found = Found {
+                    info: Info {
+                        len: label.len(),
+                        typ: None,
+                    },
+                    labels: 1,
+                    is_implicit: true,
+                    ..Found::default()
+                };
+                #[cfg(feature = "profiling")]
+                profiling::count_lookup();
+                match rules.child_bytes(label) {
+                    Some(node) => {
+                        found.info.typ = node.leaf.map(|leaf| leaf.typ);
+                        found.is_implicit = node.leaf.is_none();
+                        if let Some(leaf) = node.leaf {
+                            if typ.is_none() || typ == Some(leaf.typ) {
+                                found.set_len_of(leaf.typ, label.len());
+                            }
+                        }
+                        rules = node;
+                    }
+                    None => return found,
+                }
+                found
+            }
+            None => return Found::default(),
+        };
+
+        // a single label is fully matched by the lookup above, which is
+        // the common case when validating bare hostnames
+        let mut label = match labels.next() {
+            Some(label) => label,
+            None => return found,
+        };
+
+        // the rest of the labels
+        let mut len_so_far = found.info.len;
+        let mut labels_so_far = found.labels;
+        let mut via_wildcard = false;
+        loop {
+            #[cfg(feature = "profiling")]
+            profiling::count_lookup();
+            match rules.child_bytes(label) {
+                Some(node) => {
+                    // a `*` next to the label can lead to a longer rule
+                    if let Some(wildcard) = rules.wildcard() {
+                        #[cfg(feature = "profiling")]
+                        profiling::count_lookup();
+                        if !core::ptr::eq(node, wildcard) {
+                            let mut branches = Branches::new();
+                            branches.push((node, via_wildcard));
+                            branches.push((wildcard, true));
+                            let so_far = (len_so_far, labels_so_far);
+                            return find_branches(found, branches, label, labels, so_far, typ);
+                        }
+                    }
+                    rules = node;
+                }
+                None => {
+                    #[cfg(feature = "profiling")]
+                    if rules.has_wildcard {
+                        profiling::count_wildcard_fallback();
+                    }
+                    match rules.wildcard() {
+                        Some(node) => {
+                            rules = node;
+                            via_wildcard = true;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            let label_plus_dot = label.len() + 1;
+            if let Some(leaf) = rules.leaf {
+                if typ.is_none() || typ == Some(leaf.typ) {
+                    found.info.typ = Some(leaf.typ);
+                    found.is_exception = leaf.is_exception;
+                    found.is_wildcard = via_wildcard;
+                    found.is_implicit = false;
+                    if leaf.is_exception {
+                        found.info.len = len_so_far;
+                        found.labels = labels_so_far;
+                        found.set_len_of(leaf.typ, len_so_far);
+                        break;
+                    }
+                    found.info.len = len_so_far + label_plus_dot;
+                    found.labels = labels_so_far + 1;
+                    found.set_len_of(leaf.typ, found.info.len);
+                }
+            }
+            len_so_far += label_plus_dot;
+            labels_so_far += 1;
+            label = match labels.next() {
+                Some(label) => label,
+                None => break,
+            };
+        }
+
+        found
+    }
+
+    /// Walks the rules with the labels of a name, ignoring a trailing dot
+    ///
+    /// Names over the length limit never have a suffix. Neither do IP
+    /// addresses with the `std` feature, as they are not domain names.
+    #[inline]
+    fn find_name(&self, name: &str) -> Found {
+        self.find_name_with(name, self.typ)
+    }
+
+    /// Like `find_name` but only matching rules of type `typ`, if any
+    #[inline]
+    fn find_name_with(&self, name: &str, typ: Option<Type>) -> Found {
+        if self.is_too_long(name.as_bytes()) {
+            return Found::default();
+        }
+        #[cfg(feature = "std")]
+        {
+            if name.parse::<IpAddr>().is_ok() {
+                return Found::default();
+            }
+        }
+        self.find_name_bytes(name.as_bytes(), typ)
+    }
+
+    /// Walks the rules with the labels of a raw name, ignoring a trailing dot
+    #[inline]
+    fn find_name_bytes(&self, name: &[u8], typ: Option<Type>) -> Found {
+        self.find_match(labels_bytes(name), typ)
+    }
+
+    /// Finds the suffix of a host name given as raw bytes
+    ///
+    /// This is for names straight off the network, like `Host` headers,
+    /// which are not guaranteed to be UTF-8. The name is split on `.` and
+    /// matched as is, ignoring a trailing dot, so only the labels that
+    /// take part in the match need to be valid. With `anycase` or
+    /// `unicode`, labels have to be valid UTF-8 to be case folded or
+    /// normalised; labels that aren't can't match any rule. With the `std`
+    /// feature, IP addresses have no suffix, like in [`List::lookup`], and
+    /// neither do hosts over the [length limit](List::with_max_input_len).
+    #[inline]
+    #[must_use]
+    pub fn find_host_bytes(&self, host: &[u8]) -> Info {
+        if self.is_too_long(host) {
+            return Info { len: 0, typ: None };
+        }
+        #[cfg(feature = "std")]
+        {
+            if matches!(from_utf8(host).map(str::parse::<IpAddr>), Ok(Ok(_))) {
+                return Info { len: 0, typ: None };
+            }
+        }
+        self.find_name_bytes(host, self.typ).info
+    }
+
+    /// Copies the public suffix of `host` into `out`, returning its length
+    ///
+    /// This is [`Psl::suffix`] for callers that can't allocate, which can
+    /// keep the suffix in a buffer of their own. The suffix includes the
+    /// trailing dot if `host` has one. Returns `None` if there is no
+    /// suffix or it doesn't fit in `out`, in which case `out` is left as
+    /// it is.
+    #[inline]
+    #[must_use]
+    pub fn suffix_into(&self, host: &[u8], out: &mut [u8]) -> Option<usize> {
+        let suffix = self.suffix(host)?.as_bytes();
+        out.get_mut(..suffix.len())?.copy_from_slice(suffix);
+        Some(suffix.len())
+    }
+
+    /// Finds the suffix of `host`, rejecting malformed input
+    ///
+    /// This is [`List::find_host_bytes`] for untrusted input, where a name
+    /// that can't have a suffix should be told apart from one that has
+    /// none. A single trailing dot is allowed. With the `std` feature, IP
+    /// addresses are well-formed but have no suffix, so they give
+    /// `Info { len: 0, typ: None }`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InputTooLong` if `host` is over the
+    /// [length limit](List::with_max_input_len) and
+    /// `Error::EmptyDomainLabel` if it is empty or has an empty label.
+    pub fn try_find(&self, host: &str) -> Result<Info, Error> {
+        self.check_input_len(host)?;
+        let name = host.strip_suffix('.').unwrap_or(host);
+        if name.split('.').any(str::is_empty) {
+            return Err(Error::EmptyDomainLabel(host.to_owned()));
+        }
+        Ok(self.find_name(host).info)
+    }
+
+    /// Finds the suffix of `host`, choosing between rule types by `policy`
+    ///
+    /// The policy decides which rules count, in place of the section an
+    /// [`IcannList`] or a [`PrivateList`] keeps to. `host` is checked the
+    /// same way as in [`List::lookup`], so names over the length limit
+    /// and, with the `std` feature, IP addresses have no suffix.
+    #[must_use]
+    pub fn find_with_policy(&self, host: &str, policy: MatchPolicy) -> Info {
+        match policy {
+            MatchPolicy::IcannOnly => self.find_name_with(host, Some(Type::Icann)).info,
+            MatchPolicy::PrivateOnly => self.find_name_with(host, Some(Type::Private)).info,
+            MatchPolicy::LongestOverall => self.find_name_with(host, None).info,
+            MatchPolicy::IcannPreferred => {
+                let icann = self.find_name_with(host, Some(Type::Icann)).info;
+                if icann.typ.is_some() {
+                    return icann;
+                }
+                self.find_name_with(host, Some(Type::Private)).info
+            }
+        }
+    }
+
+    /// Returns the number of labels in the public suffix of `input`
+    ///
+    /// For example, this is `2` for `co.uk` and `1` for `com`. A trailing
+    /// dot is ignored. Returns `None` if no suffix could be found or, with
+    /// the `std` feature, if `input` is an IP address.
+    #[inline]
+    #[must_use]
+    pub fn suffix_label_count(&self, input: &str) -> Option<usize> {
+        let found = self.find_name(input);
+        if found.info.len == 0 {
+            return None;
+        }
+        Some(found.labels)
+    }
+
+    /// Returns the type of the public suffix of `input`
+    ///
+    /// Returns `None` if the suffix is not explicitly in the list,
+    /// i.e. it only matched the implicit wildcard rule, or if `input`
+    /// is an IP address and the `std` feature is enabled.
+    #[inline]
+    #[must_use]
+    pub fn suffix_type(&self, input: &str) -> Option<Type> {
+        self.find_name(input).info.typ
+    }
+
+    /// Checks whether the public suffix of `input` is a private one
+    #[inline]
+    #[must_use]
+    pub fn suffix_is_private(&self, input: &str) -> bool {
+        self.suffix_type(input) == Some(Type::Private)
+    }
+
+    /// Checks whether the public suffix of `input` is an ICANN one
+    #[inline]
+    #[must_use]
+    pub fn suffix_is_icann(&self, input: &str) -> bool {
+        self.suffix_type(input) == Some(Type::Icann)
+    }
+
+    /// Looks up everything there is to know about the suffix of `input`
+    ///
+    /// This walks the rules only once. Returns `None` if no suffix could
+    /// be found, if `input` is over the
+    /// [length limit](List::with_max_input_len) or, with the `std` feature,
+    /// if `input` is an IP address.
+    #[inline]
+    #[must_use]
+    pub fn lookup<'a>(&self, input: &'a str) -> Option<Match<'a>> {
+        Match::new(input, self.find_name(input))
+    }
+
+    /// Iterates over the candidate suffixes of `input`, one label at a time
+    ///
+    /// This follows the rules the same way as [`List::lookup`], starting from
+    /// the top-level label and adding one label per step, so rules under a
+    /// label and under a `*` next to it are both followed. Each [`Candidate`]
+    /// tells whether a rule ends at that label. Nothing is allocated along the
+    /// way, unless several `*` labels in a row make it follow many paths at
+    /// once. Like [`List::lookup`], this yields nothing for names over the
+    /// length limit or, with the `std` feature, for IP addresses.
+    #[inline]
+    pub fn matches_iter<'a>(&'a self, input: &'a str) -> MatchesIter<'a> {
+        let input = if self.is_too_long(input.as_bytes()) {
+            ""
+        } else {
+            input
+        };
+        MatchesIter::new(input, &self.rules, self.typ)
+    }
+
+    /// Finds the suffixes of many hosts in one go
+    ///
+    /// Each `Info` is what [`List::find`] returns for the labels of the
+    /// corresponding host, ignoring a trailing dot. With the `std` feature,
+    /// IP addresses get `Info { len: 0, typ: None }`.
+    pub fn find_batch<'a, I>(&self, hosts: I) -> Vec<Info>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let hosts = hosts.into_iter();
+        let mut infos = Vec::with_capacity(hosts.size_hint().0);
+        infos.extend(hosts.map(|host| self.find_name(host).info));
+        infos
+    }
+
+    /// Normalises `host` the way the rules it is matched against are
+    ///
+    /// With `punycode`, this is the ASCII form of `host` from
+    /// `idna::domain_to_ascii`, which is also lowercase. With `unicode`, `host`
+    /// is normalised to NFC and with `anycase` or `ascii-case`, ASCII letters
+    /// are lowercased. Otherwise `host` is returned as is. A trailing dot is
+    /// kept either way. The result is matched against the same rules as `host`,
+    /// except that differences in case or Unicode form no longer get in the
+    /// way, so a host can be normalised once and then looked up or stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InputTooLong` if `host` is over the
+    /// [length limit](List::with_max_input_len) or an `Err` if it can't be
+    /// converted to ASCII.
+    pub fn normalize_input<'a>(&self, host: &'a str) -> Result<Cow<'a, str>, Error> {
+        self.check_input_len(host)?;
+        #[cfg(feature = "punycode")]
+        let host = match idna::domain_to_ascii(host) {
+            Ok(ascii) if ascii == host => Cow::Borrowed(host),
+            Ok(ascii) => Cow::Owned(ascii),
+            Err(_) => return Err(Error::InvalidDomain(host.to_owned())),
+        };
+        #[cfg(not(feature = "punycode"))]
+        let host = Cow::Borrowed(host);
+        #[cfg(feature = "unicode")]
+        let host = match nfc(&host) {
+            Some(normalized) => Cow::Owned(normalized),
+            None => host,
+        };
+        #[cfg(any(feature = "anycase", feature = "ascii-case"))]
+        let host = if host.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            Cow::Owned(host.to_ascii_lowercase())
+        } else {
+            host
+        };
+        Ok(host)
+    }
+
+    /// Returns the registrable domain and the public suffix of `input`
+    ///
+    /// This walks the rules only once, unlike calling `domain` and `suffix`
+    /// separately, e.g. `("example.co.uk", "co.uk")` for `www.example.co.uk`.
+    /// Returns `None` if `input` has no registrable domain, e.g. because it
+    /// is a public suffix itself.
+    #[inline]
+    #[must_use]
+    pub fn split<'a>(&self, input: &'a str) -> Option<(&'a str, &'a str)> {
+        let found = self.lookup(input)?;
+        Some((found.registrable_domain()?, found.suffix()))
+    }
+
+    /// Returns the registrable domain of `input` as an owned value
+    ///
+    /// This is the same domain as `Psl::domain` finds, copied out of
+    /// `input` so it can outlive it. Returns `None` in the same cases as
+    /// [`List::split`].
+    #[inline]
+    #[must_use]
+    pub fn domain_owned(&self, input: &str) -> Option<OwnedDomain> {
+        OwnedDomain::new(&self.lookup(input)?)
+    }
+
+    /// Returns the domain one label above the `ICANN` suffix of `host`
+    ///
+    /// Private rules are ignored, so this is `github.io` for
+    /// `foo.github.io`, where `Psl::domain` gives `foo.github.io`. This is
+    /// the widest domain a cookie can be scoped to when only the registry
+    /// boundaries are enforced. Like with `Psl::domain`, the last label of
+    /// a name no rule matches is its suffix. Returns `None` if `host` is
+    /// itself an `ICANN` suffix or in the same cases as [`List::lookup`].
+    #[inline]
+    #[must_use]
+    pub fn cookie_domain<'a>(&self, host: &'a str) -> Option<&'a str> {
+        Match::new(host, self.find_name_with(host, Some(Type::Icann)))?.registrable_domain()
+    }
+
+    /// Checks whether the public suffix of `host` is under `tld`
+    ///
+    /// That is, whether the suffix is `tld` itself or ends with a dot
+    /// followed by `tld`, e.g. `foo.co.uk` is under `uk` and `co.uk`, but
+    /// not `k`. Trailing dots on either side are ignored and ASCII letters
+    /// are compared case insensitively. Other characters are compared as
+    /// is, so a Unicode `tld` doesn't match its punycode form or vice
+    /// versa.
+    #[must_use]
+    pub fn suffix_under(&self, host: &str, tld: &str) -> bool {
+        let tld = tld.strip_suffix('.').unwrap_or(tld);
+        let suffix = match self.lookup(host) {
+            Some(found) => found.suffix(),
+            None => return false,
+        };
+        let suffix = suffix.strip_suffix('.').unwrap_or(suffix);
+        if tld.is_empty() || suffix.len() < tld.len() {
+            return false;
+        }
+        // compare bytes, as the split may fall inside a character
+        let (rest, end) = suffix.as_bytes().split_at(suffix.len() - tld.len());
+        end.eq_ignore_ascii_case(tld.as_bytes()) && (rest.is_empty() || rest.ends_with(b"."))
+    }
+
+    /// Returns the longest public suffix shared by all of `hosts`
+    ///
+    /// This is the labels the suffixes of the hosts end with in common,
+    /// e.g. `uk` for `example.co.uk` and `example.org.uk`, or `co.uk` for
+    /// `a.example.co.uk` and `b.example.co.uk`. Trailing dots are ignored
+    /// and ASCII letters are compared case insensitively, with the case of
+    /// the first host kept. Returns `None` if there are no hosts, if one of
+    /// them has no suffix or if their suffixes have different TLDs.
+    pub fn common_suffix<'a, I>(&self, hosts: I) -> Option<String>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut hosts = hosts.into_iter();
+        let suffix = |host| {
+            let suffix = self.lookup(host)?.suffix();
+            Some(suffix.strip_suffix('.').unwrap_or(suffix))
+        };
+        let mut common = suffix(hosts.next()?)?;
+        for host in hosts {
+            let other = suffix(host)?;
+            let len = common
+                .rsplit('.')
+                .zip(other.rsplit('.'))
+                .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                .map(|(label, _)| label.len() + 1)
+                .sum::<usize>();
+            if len == 0 {
+                return None;
+            }
+            common = &common[common.len() + 1 - len..];
+        }
+        Some(common.to_owned())
+    }
+
+    /// Tells how the public suffix of `input` was determined
+    ///
+    /// This is [`Outcome::Empty`] whenever [`List::lookup`] returns `None`.
+    #[inline]
+    #[must_use]
+    pub fn outcome(&self, input: &str) -> Outcome {
+        self.lookup(input)
+            .map_or(Outcome::Empty, |found| found.outcome())
+    }
+
+    /// Returns the kind of rule that determined the public suffix of `input`
+    ///
+    /// See [`Match::rule_kind`]. Returns `None` whenever [`List::lookup`]
+    /// does.
+    #[inline]
+    #[must_use]
+    pub fn suffix_rule_kind(&self, input: &str) -> Option<RuleKind> {
+        self.lookup(input).map(|found| found.rule_kind())
+    }
+
+    /// Returns the rule that determined the public suffix of `input`
+    ///
+    /// This is the rule as written in the list, e.g. `*.kawasaki.jp` for
+    /// `foo.bar.kawasaki.jp` or `!city.kawasaki.jp` for `city.kawasaki.jp`.
+    /// If no rule matched, this is the implicit `*` rule. Returns `None` if
+    /// no suffix could be found.
+    #[must_use]
+    pub fn matching_rule(&self, input: &str) -> Option<String> {
+        let found = self.find_name(input);
+        if found.info.len == 0 {
+            return None;
+        }
+        if found.is_implicit {
+            return Some(WILDCARD.to_owned());
+        }
+        let rule_len = found.labels + usize::from(found.is_exception);
+        let labels = labels(input).take(rule_len).collect::<Vec<_>>();
+        let typ = self.typ;
+        let is_leaf = |leaf: Leaf| {
+            leaf.is_exception == found.is_exception && (typ.is_none() || typ == Some(leaf.typ))
+        };
+        // the path comes back from the last label, like rules are written
+        let rule = self.rules.rule_path(&labels, &is_leaf)?.join(".");
+        if found.is_exception {
+            return Some(format!("!{}", rule));
+        }
+        Some(rule)
+    }
+
+    /// Like [`List::lookup`] but tolerant of a fully qualified `host`
+    ///
+    /// A single trailing dot is trimmed before the lookup, so the returned
+    /// suffix never ends with one. Returns `None` if `host` has an empty
+    /// label anywhere else, e.g. `example.com..` or `.example.com`.
+    #[inline]
+    #[must_use]
+    pub fn lookup_relaxed<'a>(&self, host: &'a str) -> Option<Match<'a>> {
+        if self.is_too_long(host.as_bytes()) {
+            return None;
+        }
+        let host = host.strip_suffix('.').unwrap_or(host);
+        if host.split('.').any(str::is_empty) {
+            return None;
+        }
+        self.lookup(host)
+    }
+
+    /// Like [`List::lookup_relaxed`] but for the value of a `Host` header
+    ///
+    /// Whitespace around `value` and a trailing `:port` are stripped
+    /// first, e.g. `example.com:443` is looked up as `example.com`.
+    /// Returns `None` for IP addresses, including bracketed IPv6 ones like
+    /// `[::1]:8080`, and for malformed values, like an empty host, a port
+    /// that isn't a number or whitespace within the host. IPv4 addresses
+    /// are only recognised with the `std` feature.
+    #[must_use]
+    pub fn lookup_host_header<'a>(&self, value: &'a str) -> Option<Match<'a>> {
+        let value = value.trim();
+        if value.starts_with('[') {
+            return None;
+        }
+        let host = match value.rsplit_once(':') {
+            Some((host, port))
+                if !port.is_empty() && port.bytes().all(|byte| byte.is_ascii_digit()) =>
+            {
+                host
+            }
+            Some(_) => return None,
+            None => value,
+        };
+        if host.is_empty() || host.contains(|c: char| c.is_whitespace() || c == ':') {
+            return None;
+        }
+        self.lookup_relaxed(host)
+    }
+}
+
+impl Psl for List {
+    /// Finds the suffix of a domain name given its labels in reverse order
+    ///
+    /// The returned `Info` is
+    ///
+    /// * `Info { len: 0, typ: None }` if there are no labels or the last
+    ///   label is empty, i.e. there is no suffix;
+    /// * the length of the last label with `typ: None` if no rule matched,
+    ///   whether or not there are more labels, i.e. the suffix comes from
+    ///   the implicit `*` rule;
+    /// * the length of the suffix and its type if a rule in the list
+    ///   matched.
+    ///
+    /// [`List::lookup`] and [`Match::outcome`] tell these cases apart
+    /// explicitly.
+    #[inline]
+    fn find<'a, T>(&self, labels: T) -> Info
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        self.find_match(labels, self.typ).info
+    }
+}
+
+/// Adds rules to the list, skipping any that are invalid
+///
+/// Use [`List::from_str`] instead if invalid rules should be reported.
+impl Extend<(String, Type)> for List {
+    #[inline]
+    fn extend<I: IntoIterator<Item = (String, Type)>>(&mut self, rules: I) {
+        let mut keys = Interner::default();
+        for (rule, typ) in rules {
+            let _ = self.add_rule(&rule, typ, &mut keys);
+        }
+    }
+}
+
+/// Builds a list from rules, skipping any that are invalid
+///
+/// Use [`List::from_str`] instead if invalid rules should be reported.
+impl FromIterator<(String, Type)> for List {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (String, Type)>>(rules: I) -> Self {
+        let mut list = List::new();
+        list.extend(rules);
+        list
+    }
+}
+
+/// The rule on a line of a list, if any
+///
+/// Only the first word of a line is read, so anything after whitespace is
+/// ignored. A `//` starts a comment even right after the rule, like in
+/// `co.uk// England`.
+#[inline]
+fn rule_of(line: &str) -> Option<&str> {
+    let word = line.split_whitespace().next()?;
+    let rule = word.split("//").next().unwrap_or(word);
+    if rule.is_empty() {
+        return None;
+    }
+    Some(rule)
+}
+
+/// The most paths through the rules a lookup follows at once
+const MAX_BRANCHES: usize = 8;
+
+/// The paths through the rules a lookup follows at once
+///
+/// The first [`MAX_BRANCHES`] paths are kept on the stack, and only the
+/// ones past them are put in a `Vec`. That takes several `*` labels in a
+/// row next to labels of their own, like `*.*.*.example` along with
+/// `foo.bar.baz.example`, so lookups hardly ever allocate.
+#[derive(Debug, Clone)]
+struct Branches<T> {
+    items: [Option<T>; MAX_BRANCHES],
+    len: usize,
+    /// The paths that didn't fit in `items`
+    spilled: Vec<T>,
+}
+
+impl<T: Copy> Branches<T> {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            items: [None; MAX_BRANCHES],
+            len: 0,
+            spilled: Vec::new(),
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, item: T) {
+        match self.items.get_mut(self.len) {
+            Some(slot) => {
+                *slot = Some(item);
+                self.len += 1;
+            }
+            None => self.spilled.push(item),
+        }
+    }
+
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    fn iter(
+        &self,
+    ) -> Chain<Copied<Flatten<slice::Iter<'_, Option<T>>>>, Copied<slice::Iter<'_, T>>> {
+        let items = self.items[..self.len].iter().flatten().copied();
+        items.chain(self.spilled.iter().copied())
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Walks the rules along every path the labels left can take
+///
+/// This is the slow path of `List::find_match`, for when a label leads
+/// both to its own node and to a `*` one, so that a longer rule may be
+/// under either. An exception rule on any path prevails and otherwise
+/// the longest rule does, preferring rules reached through the labels
+/// themselves over ones reached through a `*`. `branches` are the nodes
+/// `label` leads to, along with whether a `*` was taken on the way.
+#[cold]
+fn find_branches<'a: 'n, 'n, T>(
+    mut found: Found,
+    mut branches: Branches<(&'n Node, bool)>,
+    mut label: &'n [u8],
+    mut labels: T,
+    (mut len_so_far, mut labels_so_far): (usize, usize),
+    typ: Option<Type>,
+) -> Found
+where
+    T: Iterator<Item = &'a [u8]>,
+{
+    loop {
+        let leaves = branches.iter().filter_map(|(node, via_wildcard)| {
+            node.leaf
+                .filter(|leaf| typ.is_none() || typ == Some(leaf.typ))
+                .map(|leaf| (leaf, via_wildcard))
+        });
+        let label_plus_dot = label.len() + 1;
+        if let Some((leaf, via_wildcard)) = leaves.clone().find(|(leaf, _)| leaf.is_exception) {
+            found.info.typ = Some(leaf.typ);
+            found.is_exception = true;
+            found.is_wildcard = via_wildcard;
+            found.is_implicit = false;
+            found.info.len = len_so_far;
+            found.labels = labels_so_far;
+            found.set_len_of(leaf.typ, len_so_far);
+            return found;
+        }
+        for (i, (leaf, via_wildcard)) in leaves.enumerate() {
+            if i == 0 {
+                found.info.typ = Some(leaf.typ);
+                found.is_exception = false;
+                found.is_wildcard = via_wildcard;
+                found.is_implicit = false;
+                found.info.len = len_so_far + label_plus_dot;
+                found.labels = labels_so_far + 1;
+            }
+            found.set_len_of(leaf.typ, len_so_far + label_plus_dot);
+        }
+        len_so_far += label_plus_dot;
+        labels_so_far += 1;
+        label = match labels.next() {
+            Some(label) => label,
+            None => return found,
+        };
+
+        let mut next = Branches::new();
+        for (node, via_wildcard) in branches.iter() {
+            #[cfg(feature = "profiling")]
+            profiling::count_lookup();
+            let child = node.child_bytes(label);
+            if let Some(child) = child {
+                next.push((child, via_wildcard));
+            }
+            if let Some(wildcard) = node.wildcard() {
+                #[cfg(feature = "profiling")]
+                profiling::count_lookup();
+                if !matches!(child, Some(child) if core::ptr::eq(child, wildcard)) {
+                    next.push((wildcard, true));
+                }
+            }
+        }
+        if next.is_empty() {
+            return found;
+        }
+        branches = next;
+    }
+}
+
+/// The edit distance between `a` and `b` ignoring ASCII case, if at most `max`
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    // the distances from the prefixes of `a` to the current prefix of `b`
+    let mut row = (0..=a.len()).collect::<Vec<_>>();
+    for (j, y) in b.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = j + 1;
+        for (i, x) in a.iter().enumerate() {
+            let substitution = diagonal + usize::from(!x.eq_ignore_ascii_case(y));
+            diagonal = row[i + 1];
+            row[i + 1] = substitution.min(row[i] + 1).min(diagonal + 1);
+        }
+        if row.iter().all(|distance| *distance > max) {
+            return None;
+        }
+    }
+    Some(row[a.len()]).filter(|distance| *distance <= max)
+}
+
+/// Parses a list one line at a time
+#[derive(Debug, Default)]
+struct Parser {
+    list: List,
+    typ: Option<Type>,
+    /// If set, rules in other sections are skipped
+    only: Option<Type>,
+    keys: Interner,
+    /// The owner of the current block of private rules, if it has one
+    owner: Option<String>,
+    /// Whether a rule of the current block was recorded under its owner
+    owned: bool,
+}
+
+impl Parser {
+    #[inline]
+    fn line(&mut self, line: &str) -> Result<(), Error> {
+        match line {
+            line if line.contains("BEGIN ICANN DOMAINS") => {
+                self.typ = Some(Type::Icann);
+                self.owner = None;
+            }
+            line if line.contains("BEGIN PRIVATE DOMAINS") => {
+                self.typ = Some(Type::Private);
+                self.owner = None;
+            }
+            line if line.starts_with("//") => match self.typ {
+                None => self.list.meta.parse_comment(line),
+                // the first comment of a block names who operates its rules
+                Some(Type::Private) if self.owner.is_none() => {
+                    self.owner = Some(owner::owner_of(line));
+                    self.owned = false;
+                }
+                Some(_) => {}
+            },
+            line if line.trim().is_empty() => self.owner = None,
+            line => {
+                // control characters only end up in rules through corruption,
+                // like NUL bytes from a botched download
+                if line
+                    .chars()
+                    .any(|c| c.is_control() && !matches!(c, '\t' | '\r' | '\n'))
+                {
+                    return Err(Error::InvalidRule(line.to_owned()));
+                }
+                if let Some(rule) = rule_of(line) {
+                    self.rule(rule)?;
+                }
             }
-
-            #[cfg(not(feature = "anycase"))]
-            let key = label.as_bytes().to_owned();
-            #[cfg(feature = "anycase")]
-            let key = UniCase::new(Cow::from(label.to_owned()));
-
-            current = current.children.entry(key).or_default();
         }
-
-        current.leaf = Some(Leaf { is_exception, typ });
-
         Ok(())
     }
-}
 
-#[cfg(feature = "anycase")]
-macro_rules! anycase_key {
-    ($label:ident) => {
-        match from_utf8($label) {
-            Ok(label) => UniCase::new(Cow::from(label)),
-            Err(_) => return Info { len: 0, typ: None },
+    /// Starts another list, whose rules come before any section again
+    #[inline]
+    fn next_source(&mut self) {
+        self.typ = None;
+        self.owner = None;
+        #[cfg(feature = "sources")]
+        {
+            self.keys.source = Some(self.keys.source.map_or(0, |source| source + 1));
         }
-    };
-}
+    }
 
-impl Psl for List {
+    /// Adds a rule to the current section
     #[inline]
-    fn find<'a, T>(&self, mut labels: T) -> Info
-    where
-        T: Iterator<Item = &'a [u8]>,
-    {
-        let mut rules = &self.rules;
-
-        // the first label
-        // it's special because we always need it whether or not
-        // it's in our hash map (because of the implicit wildcard)
-        let mut info = match labels.next() {
-            Some(label) => {
-                let mut info = Info {
-                    len: label.len(),
-                    typ: None,
-                };
-                #[cfg(not(feature = "anycase"))]
-                let node_opt = rules.children.get(label);
-                #[cfg(feature = "anycase")]
-                let node_opt = rules.children.get(&anycase_key!(label));
-                match node_opt {
-                    Some(node) => {
-                        info.typ = node.leaf.map(|leaf| leaf.typ);
-                        rules = node;
-                    }
-                    None => return info,
+    fn rule(&mut self, rule: &str) -> Result<(), Error> {
+        match self.typ {
+            Some(typ) => {
+                if matches!(self.only, Some(only) if only != typ) {
+                    return Ok(());
                 }
-                info
-            }
-            None => return Info { len: 0, typ: None },
-        };
-
-        // the rest of the labels
-        let mut len_so_far = info.len;
-        for label in labels {
-            #[cfg(not(feature = "anycase"))]
-            let node_opt = rules.children.get(label);
-            #[cfg(feature = "anycase")]
-            let node_opt = rules.children.get(&anycase_key!(label));
-            match node_opt {
-                Some(node) => rules = node,
-                None => {
-                    #[cfg(not(feature = "anycase"))]
-                    let node_opt = rules.children.get(WILDCARD.as_bytes());
-                    #[cfg(feature = "anycase")]
-                    let node_opt = rules.children.get(&UniCase::new(Cow::from(WILDCARD)));
-                    match node_opt {
-                        Some(node) => rules = node,
-                        None => break,
+                self.list.add_rule(rule, typ, &mut self.keys)?;
+                if let (Type::Private, Some(owner)) = (typ, &self.owner) {
+                    let owners = &mut self.list.meta.owners;
+                    if !self.owned {
+                        owners.push((owner.clone(), Vec::new()));
+                        self.owned = true;
                     }
-                }
-            }
-            let label_plus_dot = label.len() + 1;
-            if let Some(leaf) = rules.leaf {
-                if self.typ.is_none() || self.typ == Some(leaf.typ) {
-                    info.typ = Some(leaf.typ);
-                    if leaf.is_exception {
-                        info.len = len_so_far;
-                        break;
+                    if let Some((_, rules)) = owners.last_mut() {
+                        rules.push(rule.to_owned());
                     }
-                    info.len = len_so_far + label_plus_dot;
                 }
             }
-            len_so_far += label_plus_dot;
+            None => return Err(Error::RuleBeforeSection(rule.to_owned())),
         }
+        Ok(())
+    }
 
-        info
+    #[inline]
+    fn finish(self) -> Result<List, Error> {
+        if self.list.is_empty() {
+            return Err(Error::InvalidList);
+        }
+        Ok(self.list)
     }
 }
 
@@ -200,43 +2148,11 @@ impl FromStr for List {
 
     #[inline]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut typ = None;
-        let mut list = List::new();
+        let mut parser = Parser::default();
         for line in s.lines() {
-            match line {
-                line if line.contains("BEGIN ICANN DOMAINS") => {
-                    typ = Some(Type::Icann);
-                }
-                line if line.contains("BEGIN PRIVATE DOMAINS") => {
-                    typ = Some(Type::Private);
-                }
-                line if line.starts_with("//") => {
-                    continue;
-                }
-                line => match typ {
-                    Some(typ) => {
-                        let rule = match line.split_whitespace().next() {
-                            Some(rule) => rule,
-                            None => continue,
-                        };
-                        list.append(rule, typ)?;
-                        #[cfg(feature = "punycode")]
-                        {
-                            let ascii = idna::domain_to_ascii(rule)
-                                .map_err(|_| Error::InvalidRule(rule.to_owned()))?;
-                            list.append(&ascii, typ)?;
-                        }
-                    }
-                    None => {
-                        continue;
-                    }
-                },
-            }
-        }
-        if list.is_empty() {
-            return Err(Error::InvalidList);
+            parser.line(line)?;
         }
-        Ok(list)
+        parser.finish()
     }
 }
 
@@ -260,6 +2176,13 @@ impl From<IcannList> for List {
     }
 }
 
+impl AsRef<List> for IcannList {
+    #[inline]
+    fn as_ref(&self) -> &List {
+        &self.0
+    }
+}
+
 impl IcannList {
     /// Creates a new list from a byte slice
     ///
@@ -273,6 +2196,27 @@ impl IcannList {
         Ok(list.into())
     }
 
+    /// Creates a new list from a string, skipping the private section
+    ///
+    /// Unlike parsing the whole list and converting it, the private rules
+    /// are never added, so the list takes much less memory. Lookups give
+    /// the same results either way.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the format of the list is invalid or if it
+    /// has no ICANN rules.
+    pub fn from_str_icann_only(s: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            only: Some(Type::Icann),
+            ..Parser::default()
+        };
+        for line in s.lines() {
+            parser.line(line)?;
+        }
+        parser.finish().map(Self::from)
+    }
+
     /// Checks to see if the list is empty, ignoring the wildcard rule
     #[inline]
     #[must_use]
@@ -321,6 +2265,13 @@ impl From<PrivateList> for List {
     }
 }
 
+impl AsRef<List> for PrivateList {
+    #[inline]
+    fn as_ref(&self) -> &List {
+        &self.0
+    }
+}
+
 impl PrivateList {
     /// Creates a new list from a byte slice
     ///
@@ -362,6 +2313,58 @@ impl Psl for PrivateList {
     }
 }
 
+/// A list that is cheap to clone
+///
+/// Cloning a [`List`] copies all of its rules, while cloning a
+/// `SharedList` only bumps a reference count, with the rules shared by
+/// all the clones. Use this when the same list is handed to many threads,
+/// tasks or requests, and a plain `List` when it is built once and used
+/// in place, or needs changing, which a shared list can't do.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SharedList(Arc<List>);
+
+impl From<List> for SharedList {
+    #[inline]
+    fn from(list: List) -> Self {
+        Self(Arc::new(list))
+    }
+}
+
+impl From<SharedList> for List {
+    /// Takes the list back, copying its rules only if it is still shared
+    #[inline]
+    fn from(SharedList(list): SharedList) -> Self {
+        Arc::try_unwrap(list).unwrap_or_else(|list| (*list).clone())
+    }
+}
+
+impl AsRef<List> for SharedList {
+    #[inline]
+    fn as_ref(&self) -> &List {
+        &self.0
+    }
+}
+
+impl FromStr for SharedList {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let list = List::from_str(s)?;
+        Ok(list.into())
+    }
+}
+
+impl Psl for SharedList {
+    #[inline]
+    fn find<'a, T>(&self, labels: T) -> Info
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        self.0.find(labels)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,12 +2379,15 @@ mod tests {
         let list = List::from_bytes(LIST).unwrap();
         let expected = List {
             typ: None,
+            meta: ListMeta::default(),
+            max_input_len: MAX_INPUT_LEN,
+            allow_underscores: true,
             rules: Node {
                 children: {
                     let mut children = Children::default();
                     children.insert(
                         #[cfg(not(feature = "anycase"))]
-                        b"uk".to_vec(),
+                        Key::from(&b"uk"[..]),
                         #[cfg(feature = "anycase")]
                         UniCase::new(Cow::from("uk")),
                         Node {
@@ -389,7 +2395,7 @@ mod tests {
                                 let mut children = Children::default();
                                 children.insert(
                                     #[cfg(not(feature = "anycase"))]
-                                    b"com".to_vec(),
+                                    Key::from(&b"com"[..]),
                                     #[cfg(feature = "anycase")]
                                     UniCase::new(Cow::from("com")),
                                     Node {
@@ -397,22 +2403,359 @@ mod tests {
                                         leaf: Some(Leaf {
                                             is_exception: false,
                                             typ: Type::Icann,
+                                            #[cfg(feature = "sources")]
+                                            source: None,
                                         }),
+                                        has_wildcard: false,
                                     },
                                 );
                                 children
                             },
                             leaf: None,
+                            has_wildcard: false,
                         },
                     );
                     children
                 },
                 leaf: None,
+                has_wildcard: false,
             },
         };
         assert_eq!(list, expected);
     }
 
+    #[test]
+    fn wildcard_flags() {
+        fn check(node: &Node) {
+            let has_wildcard = node.children.keys().any(|key| label(key) == WILDCARD);
+            assert_eq!(node.has_wildcard, has_wildcard);
+            node.children.values().for_each(check);
+        }
+
+        let mut list =
+            List::from_bytes(b"// BEGIN ICANN DOMAINS\n*\n*.ck\n!www.ck\nuk\n*.sch.uk\nfoo.bar.jp")
+                .unwrap();
+        check(&list.rules);
+        assert!(list.rules.has_wildcard);
+        assert!(list.rules.wildcard().is_some());
+        assert!(list.rules.child("uk").unwrap().wildcard().is_none());
+
+        list.subtract(&List::from_bytes(b"// BEGIN ICANN DOMAINS\n*.sch.uk").unwrap());
+        check(&list.rules);
+        assert!(list.rule_info("*.ck").is_some());
+
+        let ck = list.extract_subtree("ck").unwrap();
+        check(&ck.rules);
+        let root = list.extract_subtree("*").unwrap();
+        check(&root.rules);
+
+        list.retain_tlds(&["jp"]);
+        check(&list.rules);
+        assert!(!list.rules.has_wildcard);
+    }
+
+    #[test]
+    #[cfg(not(feature = "anycase"))]
+    fn labels_share_storage() {
+        let list = List::from_bytes(b"// BEGIN ICANN DOMAINS\nco.uk\nco.jp").unwrap();
+        let (uk, _) = list.rules.children[&b"uk"[..]]
+            .children
+            .get_key_value(&b"co"[..])
+            .unwrap();
+        let (jp, _) = list.rules.children[&b"jp"[..]]
+            .children
+            .get_key_value(&b"co"[..])
+            .unwrap();
+        assert!(Arc::ptr_eq(uk, jp));
+    }
+
+    #[test]
+    fn section_introspection() {
+        let list = List::from_bytes(LIST).unwrap();
+        assert!(list.is_icann_only());
+        assert!(!list.is_private_only());
+
+        let list = List::from_bytes(b"// BEGIN PRIVATE DOMAINS\nfoo.uk").unwrap();
+        assert!(list.is_private_only());
+        assert!(!list.is_icann_only());
+
+        let both = b"// BEGIN ICANN DOMAINS\ncom.uk\n// BEGIN PRIVATE DOMAINS\nfoo.uk";
+        let list = List::from_bytes(both).unwrap();
+        assert!(!list.is_icann_only());
+        assert!(!list.is_private_only());
+        assert!(IcannList::from(list).as_ref().is_icann_only());
+
+        assert!(!List::new().is_icann_only());
+        assert!(!List::new().is_private_only());
+    }
+
+    #[test]
+    fn iter_tlds() {
+        let list =
+            List::from_bytes(b"// BEGIN ICANN DOMAINS\nuk\ncom.uk\n*\njp\n*.kobe.jp").unwrap();
+        assert_eq!(list.iter_tlds().collect::<Vec<_>>(), ["jp", "uk"]);
+        assert!(list.iter_tlds().all(|tld| list.is_known_tld(tld)));
+    }
+
+    #[test]
+    fn special_use() {
+        let list = List::from_bytes(LIST).unwrap().with_special_use();
+        assert_eq!(list.suffix_type("com.uk"), Some(Type::Icann));
+        for name in [
+            "foo.test",
+            "foo.localhost",
+            "foo.invalid",
+            "foo.example",
+            "foo.local",
+        ] {
+            assert_eq!(list.suffix_type(name), Some(Type::Icann));
+        }
+        assert!(List::new().with_special_use().is_icann_only());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ip_addresses_have_no_suffix() {
+        let list = List::from_bytes(LIST).unwrap();
+        assert!(list.lookup("127.38.53.247").is_none());
+        assert!(list
+            .lookup("fd79:cdcb:38cc:9dd:f686:e06d:32f3:c123")
+            .is_none());
+        assert_eq!(list.suffix_label_count("127.38.53.247"), None);
+        assert_eq!(list.lookup("127.com.uk").unwrap().suffix(), "com.uk");
+    }
+
+    #[test]
+    fn walk() {
+        let rules = b"// BEGIN ICANN DOMAINS\ncom.uk\n*.kawasaki.jp\n!city.kawasaki.jp";
+        let list = List::from_bytes(rules).unwrap();
+        let mut visited = Vec::new();
+        list.walk(|path, info| visited.push((path.join("."), info)));
+        let rule = |is_exception| RuleInfo {
+            is_exception,
+            typ: Type::Icann,
+        };
+        assert_eq!(
+            visited,
+            [
+                ("jp.kawasaki.*".to_owned(), rule(false)),
+                ("jp.kawasaki.city".to_owned(), rule(true)),
+                ("uk.com".to_owned(), rule(false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn walk_is_deterministic() {
+        let rules = b"// BEGIN ICANN DOMAINS\nb.a\na\nc.b.a\nb\na.b.a\na.a\nc";
+        let list = List::from_bytes(rules).unwrap();
+        let walk = |list: &List| {
+            let mut visited = Vec::new();
+            list.walk(|path, _| visited.push(path.join(".")));
+            visited
+        };
+        let expected = ["a", "a.a", "a.b", "a.b.a", "a.b.c", "b", "c"];
+        assert_eq!(walk(&list), expected);
+        assert_eq!(walk(&list), walk(&list));
+
+        let reordered = b"// BEGIN ICANN DOMAINS\nc\na.a\nb\na\na.b.a\nc.b.a\nb.a";
+        assert_eq!(walk(&List::from_bytes(reordered).unwrap()), expected);
+    }
+
+    #[test]
+    fn find_shadowed() {
+        let rules = "// BEGIN ICANN DOMAINS\nuk\n*.uk\nfoo.uk\nbar.uk\nbaz.bar.uk\n!qux.uk\n\
+            *\ncom\n// BEGIN PRIVATE DOMAINS\nprivate.uk\n*.github.io\nfoo.github.io";
+        let list = List::from_bytes(rules.as_bytes()).unwrap();
+        assert_eq!(list.find_shadowed(), ["foo.github.io", "foo.uk"]);
+
+        let mut pruned = list.clone();
+        pruned.subtract(&List::from_bytes(b"// BEGIN ICANN DOMAINS\nfoo.uk").unwrap());
+        for name in ["a.foo.uk", "foo.uk", "a.bar.uk", "qux.uk"] {
+            assert_eq!(
+                pruned.lookup(name).map(|found| found.suffix()),
+                list.lookup(name).map(|found| found.suffix())
+            );
+        }
+    }
+
+    #[test]
+    fn validate() {
+        let mut list =
+            List::from_bytes(b"// BEGIN ICANN DOMAINS\ncom.uk\n!city.kawasaki.jp").unwrap();
+        assert_eq!(list.validate(), Ok(()));
+        assert!(List::new().is_valid());
+
+        let mut keys = Interner::default();
+        let mut broken = list.clone();
+        broken
+            .rules
+            .children
+            .insert(keys.key("jp"), Node::default());
+        assert_eq!(broken.validate(), Err(Error::InvalidRule("jp".into())));
+
+        let mut broken = list.clone();
+        let uk = broken.rules.children.get_mut(&keys.key("uk")).unwrap();
+        uk.children
+            .insert(keys.key(""), uk.children[&keys.key("com")].clone());
+        assert_eq!(broken.validate(), Err(Error::EmptyLabel(".uk".into())));
+
+        let mut broken = list.clone();
+        let leaf = Leaf {
+            is_exception: true,
+            typ: Type::Icann,
+            #[cfg(feature = "sources")]
+            source: None,
+        };
+        broken.rules.children.get_mut(&keys.key("uk")).unwrap().leaf = Some(leaf);
+        assert_eq!(
+            broken.validate(),
+            Err(Error::ExceptionAtFirstLabel("!uk".into()))
+        );
+        assert!(!broken.is_valid());
+
+        list.rules.children.insert(
+            keys.key("co.jp"),
+            broken.rules.children[&keys.key("uk")].clone(),
+        );
+        assert!(matches!(list.validate(), Err(Error::InvalidRule(_))));
+    }
+
+    #[test]
+    fn dedup() {
+        let mut list = List::from_bytes(
+            b"// BEGIN ICANN DOMAINS\nuk\nco.uk\nfoo.bar.jp\n*.ck\n!www.ck\n*.kawasaki.jp",
+        )
+        .unwrap();
+        list.subtract(&List::from_bytes(b"// BEGIN ICANN DOMAINS\nco.uk").unwrap());
+        assert!(list.is_valid());
+
+        // drop leaves without pruning, like a list saved by another tool may
+        let keys = &mut Interner::default();
+        let jp = list.rules.children.get_mut(&keys.key("jp")).unwrap();
+        let bar = jp.children.get_mut(&keys.key("bar")).unwrap();
+        bar.children.get_mut(&keys.key("foo")).unwrap().leaf = None;
+        let ck = list.rules.children.get_mut(&keys.key("ck")).unwrap();
+        ck.children.get_mut(&keys.key("*")).unwrap().leaf = None;
+        let suffix = |list: &List| list.suffix(b"a.www.ck").unwrap().as_bytes().to_vec();
+        let before = suffix(&list);
+        assert!(!list.is_valid());
+
+        list.dedup();
+        assert!(list.is_valid());
+        assert_eq!(suffix(&list), before);
+        assert!(!list.rules.children[&keys.key("ck")].has_wildcard);
+        assert_eq!(list.rules.children[&keys.key("jp")].children.len(), 1);
+        let deduped = list.clone();
+        list.dedup();
+        assert_eq!(list, deduped);
+    }
+
+    #[test]
+    fn rule_before_section() {
+        let error = List::from_bytes(b"com.uk\nco.uk").unwrap_err();
+        assert_eq!(error, Error::RuleBeforeSection("com.uk".into()));
+        let error =
+            List::from_bytes(b"// header\n\ncom.uk\n// BEGIN ICANN DOMAINS\nco.uk").unwrap_err();
+        assert_eq!(error, Error::RuleBeforeSection("com.uk".into()));
+        assert!(List::from_bytes(b"// header\n  \n// BEGIN ICANN DOMAINS\nco.uk").is_ok());
+    }
+
+    #[test]
+    fn control_characters() {
+        let error =
+            List::from_bytes(b"// BEGIN ICANN DOMAINS\nco.uk\nex\0ample.com\n").unwrap_err();
+        assert_eq!(error, Error::InvalidRule("ex\0ample.com".into()));
+        let error = List::from_bytes(b"// BEGIN ICANN DOMAINS\nuk\x1b\n").unwrap_err();
+        assert_eq!(error, Error::InvalidRule("uk\x1b".into()));
+        // tabs and carriage returns are just whitespace
+        let list = List::from_bytes(b"// BEGIN ICANN DOMAINS\r\nco.uk\t// UK\r\nuk\r\n").unwrap();
+        assert_eq!(list.suffix(b"example.co.uk").unwrap(), "co.uk");
+    }
+
+    #[test]
+    fn list_meta() {
+        let list = List::from_bytes(LIST).unwrap();
+        assert_eq!(list.meta(), &ListMeta::default());
+
+        let rules = b"// VERSION: 2024-06-26_08-23-25_UTC\n\
+            // COMMIT: 9f7b2a2\n\
+            // Fetched: 2024-06-27T00:00:00Z\n\
+            // BEGIN ICANN DOMAINS\n\
+            // VERSION: not a header\n\
+            com.uk";
+        let list = List::from_bytes(rules).unwrap();
+        assert_eq!(
+            list.meta().version.as_deref(),
+            Some("2024-06-26_08-23-25_UTC")
+        );
+        assert_eq!(list.meta().fetched.as_deref(), Some("2024-06-27T00:00:00Z"));
+    }
+
+    #[test]
+    fn insert_checked() {
+        let mut list = List::new();
+        assert_eq!(list.insert_checked("co.uk", Type::Icann), Ok(true));
+        assert_eq!(list.insert_checked("co.uk", Type::Private), Ok(false));
+        assert_eq!(list.suffix_type("example.co.uk"), Some(Type::Private));
+        assert_eq!(list.insert_checked("食狮.中国", Type::Icann), Ok(true));
+        assert_eq!(list.insert_checked("食狮.中国", Type::Icann), Ok(false));
+        assert_eq!(
+            list.insert_checked("!uk", Type::Icann),
+            Err(Error::ExceptionAtFirstLabel("!uk".to_owned()))
+        );
+
+        assert_eq!(
+            list.insert_checked("a..b", Type::Icann),
+            Err(Error::EmptyLabel("a..b".to_owned()))
+        );
+        assert_eq!(
+            list.insert_checked(".com", Type::Icann),
+            Err(Error::LeadingDot(".com".to_owned()))
+        );
+        assert_eq!(
+            list.insert_checked("!.kawasaki.jp", Type::Icann),
+            Err(Error::LeadingDot(".kawasaki.jp".to_owned()))
+        );
+        assert_eq!(
+            list.insert_checked("com.", Type::Icann),
+            Err(Error::EmptyLabel("com.".to_owned()))
+        );
+        assert!(!list.is_known_tld("b"));
+    }
+
+    #[test]
+    fn typed_inserts() {
+        let mut list = List::new();
+        assert_eq!(list.insert_icann("co.uk"), Ok(true));
+        assert_eq!(list.insert_private("internal.corp"), Ok(true));
+        assert_eq!(list.suffix_type("example.co.uk"), Some(Type::Icann));
+        assert_eq!(
+            list.suffix_type("example.internal.corp"),
+            Some(Type::Private)
+        );
+        assert_eq!(list.insert_private("co.uk"), Ok(false));
+        assert_eq!(list.suffix_type("example.co.uk"), Some(Type::Private));
+        assert!(list.insert_icann("!uk").is_err());
+    }
+
+    #[test]
+    fn inline_comments() {
+        let list = "// ===BEGIN ICANN DOMAINS===
+co.uk // England
+org.uk// Wales
+ac.uk\t// Scotland
+  // an indented comment
+gov.uk"
+            .parse::<List>()
+            .unwrap();
+        for rule in ["co.uk", "org.uk", "ac.uk", "gov.uk"] {
+            assert!(list.rule_info(rule).is_some(), "{}", rule);
+        }
+        assert_eq!(list.iter_tlds().collect::<Vec<_>>(), ["uk"]);
+        assert_eq!(list.suffix_type("example.org.uk"), Some(Type::Icann));
+    }
+
     #[test]
     fn find_localhost() {
         let list = List::from_bytes(LIST).unwrap();
@@ -440,6 +2783,134 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn list_from_file() {
+        let list = List::from_file("tests/public_suffix_list.dat").unwrap();
+        let labels = b"example.co.uk".rsplit(|x| *x == b'.');
+        assert_eq!(
+            list.find(labels),
+            Info {
+                len: 5,
+                typ: Some(Type::Icann)
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn list_from_missing_file() {
+        let error = List::from_file("tests/does-not-exist.dat").unwrap_err();
+        assert_eq!(error, Error::Io(std::io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn list_from_reader() {
+        let list = List::from_reader(LIST).unwrap();
+        assert_eq!(list, List::from_bytes(LIST).unwrap());
+
+        let crlf = b"// BEGIN ICANN DOMAINS\r\ncom.uk\r\n";
+        assert_eq!(List::from_reader(&crlf[..]).unwrap(), list);
+
+        let invalid = b"// BEGIN ICANN DOMAINS\ncom.\xff\n";
+        let error = List::from_reader(&invalid[..]).unwrap_err();
+        assert_eq!(error, Error::InvalidUtf8 { valid_up_to: 27 });
+        assert_eq!(List::from_bytes(invalid).unwrap_err(), error);
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn list_from_compressed() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(LIST).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let list = List::from_compressed(&compressed).unwrap();
+        assert_eq!(list, List::from_bytes(LIST).unwrap());
+
+        assert!(matches!(List::from_compressed(LIST), Err(Error::Io(_))));
+        let truncated = &compressed[..compressed.len() / 2];
+        assert!(List::from_compressed(truncated).is_err());
+    }
+
+    #[test]
+    fn suffix_label_count() {
+        let list = List::from_bytes(LIST).unwrap();
+        assert_eq!(list.suffix_label_count("www.example.com.uk"), Some(2));
+        assert_eq!(list.suffix_label_count("example.com.uk."), Some(2));
+        assert_eq!(list.suffix_label_count("localhost"), Some(1));
+        assert_eq!(list.suffix_label_count(""), None);
+    }
+
+    #[test]
+    fn find_with_non_utf8_labels() {
+        let list = List::from_bytes(b"// BEGIN ICANN DOMAINS\ncom.uk\n*.ck").unwrap();
+        let labels = b"\xff.example.com.uk".rsplit(|x| *x == b'.');
+        assert_eq!(
+            list.find(labels),
+            Info {
+                len: 6,
+                typ: Some(Type::Icann)
+            }
+        );
+        assert_eq!(list.suffix(b"\xff.com.uk").unwrap().as_bytes(), b"com.uk");
+        assert_eq!(
+            list.domain(b"\xff.com.uk").unwrap().as_bytes(),
+            b"\xff.com.uk"
+        );
+        assert_eq!(list.suffix(b"\xff.ck").unwrap().as_bytes(), b"\xff.ck");
+        assert_eq!(list.suffix(b"\xff").unwrap().as_bytes(), b"\xff");
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn unicode_normalization() {
+        let list =
+            List::from_bytes("// BEGIN ICANN DOMAINS\n中国\ncafe\u{301}.fr".as_bytes()).unwrap();
+        for name in [
+            "www.食狮.中国",
+            "www.食狮.中国".nfd().collect::<String>().as_str(),
+        ] {
+            assert_eq!(
+                list.suffix(name.as_bytes()).unwrap().as_bytes(),
+                "中国".as_bytes()
+            );
+        }
+        let composed = "www.caf\u{e9}.fr";
+        let decomposed = "www.cafe\u{301}.fr";
+        assert_eq!(
+            list.suffix(composed.as_bytes()).unwrap().as_bytes(),
+            "caf\u{e9}.fr".as_bytes()
+        );
+        assert_eq!(
+            list.suffix(decomposed.as_bytes()).unwrap().as_bytes(),
+            "cafe\u{301}.fr".as_bytes()
+        );
+        assert!(list.is_known_tld("中国"));
+        assert_eq!(
+            list.matching_rule(decomposed).as_deref(),
+            Some("caf\u{e9}.fr")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "punycode")]
+    fn punycode_is_nontransitional() {
+        let list = List::from_bytes("// BEGIN ICANN DOMAINS\nfaß.de\nας.gr".as_bytes()).unwrap();
+        let suffix = |name: &str| list.suffix(name.as_bytes()).map(|suffix| suffix.typ());
+        // ß and ς are kept as they are, like IDNA2008 does
+        assert_eq!(suffix("www.xn--fa-hia.de"), Some(Some(Type::Icann)));
+        assert_eq!(suffix("www.xn--mxa8a.gr"), Some(Some(Type::Icann)));
+        // instead of being mapped to ss and σ by transitional processing
+        // (case folding does map ß to ss, so anycase matches it anyway)
+        #[cfg(not(feature = "anycase"))]
+        assert_eq!(suffix("www.fass.de"), Some(None));
+        assert_eq!(suffix("www.xn--mxa0b.gr"), Some(None));
+    }
+
     #[test]
     fn find_ide_kyoto_jp() {
         let list = List::from_bytes(b"// BEGIN ICANN DOMAINS\nide.kyoto.jp").unwrap();
@@ -0,0 +1,394 @@
+use crate::{Branches, Found, Node, RuleInfo, Type};
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+
+/// How the public suffix of a domain name was determined
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Outcome {
+    /// There is no suffix, e.g. because the input is empty
+    Empty,
+    /// No rule matched, so the implicit `*` rule made the TLD the suffix
+    ImplicitWildcard,
+    /// A rule in the list determined the suffix
+    KnownSuffix,
+}
+
+/// The kind of rule that determined a public suffix
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum RuleKind {
+    /// A plain rule like `co.uk`
+    Normal,
+    /// A rule with a `*` label like `*.kawasaki.jp`, or the implicit `*` rule
+    Wildcard,
+    /// An exception rule like `!city.kawasaki.jp`
+    Exception,
+}
+
+/// Which types of rules decide the suffix of a domain name
+///
+/// Used by [`List::find_with_policy`](crate::List::find_with_policy).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum MatchPolicy {
+    /// Only `ICANN` rules count, like with an [`IcannList`](crate::IcannList)
+    IcannOnly,
+    /// Only `private` rules count, like with a
+    /// [`PrivateList`](crate::PrivateList)
+    PrivateOnly,
+    /// The longest matching rule counts, whatever its type, like with a
+    /// [`List`](crate::List)
+    LongestOverall,
+    /// `ICANN` rules count first, and `private` ones only if no `ICANN`
+    /// rule matches at all
+    ///
+    /// This keeps suffixes at the registry boundary where there is one,
+    /// e.g. for scoping cookies, while still finding suffixes under TLDs
+    /// that are only in the private section.
+    IcannPreferred,
+}
+
+/// The result of looking up a domain name in a list
+///
+/// The suffix and the registrable domain are slices of the name that
+/// was looked up, so they keep its case even when rules are matched
+/// case insensitively with the `anycase` feature.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Match<'a> {
+    name: &'a str,
+    suffix: &'a str,
+    icann_suffix: Option<&'a str>,
+    private_suffix: Option<&'a str>,
+    typ: Option<Type>,
+    is_exception: bool,
+    is_wildcard: bool,
+    is_implicit: bool,
+}
+
+impl<'a> Match<'a> {
+    #[inline]
+    pub(crate) fn new(name: &'a str, found: Found) -> Option<Self> {
+        if found.info.len == 0 {
+            return None;
+        }
+        let dot = usize::from(name.ends_with('.'));
+        let slice = |len: usize| name.get(name.len() - (len + dot)..);
+        let rule_slice = |len: usize| if len == 0 { None } else { slice(len) };
+        Some(Self {
+            name,
+            suffix: slice(found.info.len)?,
+            icann_suffix: rule_slice(found.icann_len),
+            private_suffix: rule_slice(found.private_len),
+            typ: found.info.typ,
+            is_exception: found.is_exception,
+            is_wildcard: found.is_wildcard,
+            is_implicit: found.is_implicit,
+        })
+    }
+
+    /// The public suffix, including the trailing dot if the input had one
+    #[inline]
+    #[must_use]
+    pub const fn suffix(&self) -> &'a str {
+        self.suffix
+    }
+
+    /// The longest suffix matched by an `ICANN` rule, if any
+    ///
+    /// For `foo.github.io`, this is `io` while the suffix is `github.io`.
+    /// It comes from the same walk of the rules as the suffix, so it is
+    /// never longer than it.
+    #[inline]
+    #[must_use]
+    pub const fn icann_suffix(&self) -> Option<&'a str> {
+        self.icann_suffix
+    }
+
+    /// The longest suffix matched by a `private` rule, if any
+    ///
+    /// For `foo.github.io`, this is `github.io`, the same as the suffix.
+    #[inline]
+    #[must_use]
+    pub const fn private_suffix(&self) -> Option<&'a str> {
+        self.private_suffix
+    }
+
+    /// The registrable domain, i.e. the suffix plus one more label
+    ///
+    /// Returns `None` if the input is itself a public suffix.
+    #[inline]
+    #[must_use]
+    pub fn registrable_domain(&self) -> Option<&'a str> {
+        let name_len = self.name.len();
+        let suffix_len = self.suffix.len();
+        if name_len < suffix_len + 2 {
+            return None;
+        }
+        let subdomain = self.name.get(..name_len - (1 + suffix_len))?;
+        let root_label = subdomain.rsplit('.').next()?;
+        if root_label.is_empty() {
+            return None;
+        }
+        self.name
+            .get(name_len - (root_label.len() + 1 + suffix_len)..)
+    }
+
+    /// Whether this is an `ICANN`, `private` or unknown suffix
+    #[inline]
+    #[must_use]
+    pub const fn typ(&self) -> Option<Type> {
+        self.typ
+    }
+
+    /// Whether or not this is a known suffix, i.e. one explicitly in the list
+    #[inline]
+    #[must_use]
+    pub const fn is_known(&self) -> bool {
+        self.typ.is_some()
+    }
+
+    /// Whether or not the suffix was determined by an exception rule
+    #[inline]
+    #[must_use]
+    pub const fn is_exception(&self) -> bool {
+        self.is_exception
+    }
+
+    /// Whether or not the matching rule was reached through a `*` label
+    #[inline]
+    #[must_use]
+    pub const fn is_wildcard_match(&self) -> bool {
+        self.is_wildcard
+    }
+
+    /// Whether or not the suffix comes from the implicit `*` rule
+    ///
+    /// When no rule in the list matches, the last label of the input is
+    /// treated as its suffix. This is unlike [`Match::is_wildcard_match`],
+    /// which reports an explicit wildcard rule like `*.ck` in the list.
+    #[inline]
+    #[must_use]
+    pub const fn is_implicit_wildcard(&self) -> bool {
+        self.is_implicit
+    }
+
+    /// The kind of rule that determined the suffix
+    ///
+    /// A suffix that only matched the implicit `*` rule counts as a
+    /// wildcard one.
+    #[inline]
+    #[must_use]
+    pub const fn rule_kind(&self) -> RuleKind {
+        if self.is_exception {
+            RuleKind::Exception
+        } else if self.is_wildcard || self.is_implicit {
+            RuleKind::Wildcard
+        } else {
+            RuleKind::Normal
+        }
+    }
+
+    /// How the suffix was determined
+    ///
+    /// This is never [`Outcome::Empty`], since there is no `Match` without
+    /// a suffix.
+    #[inline]
+    #[must_use]
+    pub const fn outcome(&self) -> Outcome {
+        if self.is_known() {
+            Outcome::KnownSuffix
+        } else {
+            Outcome::ImplicitWildcard
+        }
+    }
+}
+
+/// A registrable domain that owns its data
+///
+/// Created by [`List::domain_owned`](crate::List::domain_owned). Unlike
+/// the [`Domain`](crate::Domain) returned by `Psl::domain`, it doesn't
+/// borrow the name it was found in, so it can be stored for as long as
+/// needed, e.g. as the key of a cache.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct OwnedDomain {
+    domain: String,
+    suffix_len: usize,
+    typ: Option<Type>,
+}
+
+impl OwnedDomain {
+    #[inline]
+    pub(crate) fn new(found: &Match<'_>) -> Option<Self> {
+        Some(Self {
+            domain: found.registrable_domain()?.to_owned(),
+            suffix_len: found.suffix().len(),
+            typ: found.typ(),
+        })
+    }
+
+    /// The registrable domain, including the trailing dot if the input had one
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.domain
+    }
+
+    /// The public suffix of the domain
+    #[inline]
+    #[must_use]
+    pub fn suffix(&self) -> &str {
+        &self.domain[self.domain.len() - self.suffix_len..]
+    }
+
+    /// Whether the suffix is an `ICANN`, `private` or unknown one
+    #[inline]
+    #[must_use]
+    pub const fn typ(&self) -> Option<Type> {
+        self.typ
+    }
+
+    /// Takes the registrable domain out as a string
+    #[inline]
+    #[must_use]
+    pub fn into_string(self) -> String {
+        self.domain
+    }
+}
+
+/// Serialises the suffix, its type and the registrable domain
+///
+/// The type is `"ICANN"`, `"PRIVATE"` or `null` if the suffix isn't in the
+/// list, e.g. for `www.example.co.uk`:
+///
+/// ```json
+/// {"suffix":"co.uk","type":"ICANN","registrable_domain":"example.co.uk"}
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for Match<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let typ = self.typ.map(|typ| match typ {
+            Type::Icann => "ICANN",
+            Type::Private => "PRIVATE",
+        });
+        let mut state = serializer.serialize_struct("Match", 3)?;
+        state.serialize_field("suffix", self.suffix)?;
+        state.serialize_field("type", &typ)?;
+        state.serialize_field("registrable_domain", &self.registrable_domain())?;
+        state.end()
+    }
+}
+
+/// A candidate suffix visited while matching a domain name
+///
+/// Yielded by [`MatchesIter`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Candidate<'a> {
+    suffix: &'a str,
+    rule: Option<RuleInfo>,
+}
+
+impl<'a> Candidate<'a> {
+    /// The labels visited so far, with the trailing dot if the input had one
+    #[inline]
+    #[must_use]
+    pub const fn suffix(&self) -> &'a str {
+        self.suffix
+    }
+
+    /// The rule that ends at this label, if any
+    ///
+    /// This is `None` if the labels visited so far don't complete a rule,
+    /// even if they lead to longer ones.
+    #[inline]
+    #[must_use]
+    pub const fn rule(&self) -> Option<RuleInfo> {
+        self.rule
+    }
+}
+
+/// An iterator over the candidate suffixes of a domain name
+///
+/// Created by [`List::matches_iter`](crate::List::matches_iter).
+#[derive(Debug, Clone)]
+pub struct MatchesIter<'a> {
+    name: &'a str,
+    /// The end of the labels left to visit, if any
+    end: Option<usize>,
+    /// The nodes reached so far, which are none once no rule can match anymore
+    nodes: Branches<&'a Node>,
+    is_root: bool,
+    typ: Option<Type>,
+}
+
+impl<'a> MatchesIter<'a> {
+    #[inline]
+    pub(crate) fn new(name: &'a str, rules: &'a Node, typ: Option<Type>) -> Self {
+        let end = name.strip_suffix('.').unwrap_or(name).len();
+        #[cfg(feature = "std")]
+        let end = if name.parse::<std::net::IpAddr>().is_ok() {
+            0
+        } else {
+            end
+        };
+        let mut nodes = Branches::new();
+        nodes.push(rules);
+        Self {
+            name,
+            end: if end == 0 { None } else { Some(end) },
+            nodes,
+            is_root: true,
+            typ,
+        }
+    }
+}
+
+impl<'a> Iterator for MatchesIter<'a> {
+    type Item = Candidate<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let end = self.end?;
+        let start = self.name[..end].rfind('.').map_or(0, |dot| dot + 1);
+        self.end = start.checked_sub(1);
+
+        let label = &self.name[start..end];
+        let is_root = core::mem::replace(&mut self.is_root, false);
+        let mut nodes = Branches::new();
+        for node in self.nodes.iter() {
+            let child = node.child(label);
+            if let Some(child) = child {
+                nodes.push(child);
+            }
+            // the implicit wildcard takes care of unknown top-level labels
+            if let Some(wildcard) = node.wildcard().filter(|_| !is_root) {
+                if !matches!(child, Some(child) if core::ptr::eq(child, wildcard)) {
+                    nodes.push(wildcard);
+                }
+            }
+        }
+        self.nodes = nodes;
+
+        // like in lookups, an exception prevails over other rules
+        let typ = self.typ;
+        let leaves = self
+            .nodes
+            .iter()
+            .filter_map(|node| node.leaf)
+            .filter(|leaf| typ.is_none() || typ == Some(leaf.typ));
+        let rule = leaves
+            .clone()
+            .find(|leaf| leaf.is_exception)
+            .or_else(|| leaves.clone().next())
+            .map(RuleInfo::from);
+        Some(Candidate {
+            suffix: &self.name[start..],
+            rule,
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Metadata about a list, taken from its comments
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct ListMeta {
+    /// The value of a `// VERSION:` comment, if any
+    pub version: Option<String>,
+    /// The value of a `// Fetched:` comment, if any
+    pub fetched: Option<String>,
+    /// The owner of each block of private rules and the rules in it, in
+    /// the order they appear in the list
+    pub(crate) owners: Vec<(String, Vec<String>)>,
+}
+
+impl ListMeta {
+    /// Records the value of a comment if it's one we recognise
+    #[inline]
+    pub(crate) fn parse_comment(&mut self, line: &str) {
+        let comment = line.trim_start_matches('/').trim();
+        if let Some(version) = comment.strip_prefix("VERSION:") {
+            self.version = Some(version.trim().to_owned());
+        } else if let Some(fetched) = comment.strip_prefix("Fetched:") {
+            self.fetched = Some(fetched.trim().to_owned());
+        }
+    }
+}
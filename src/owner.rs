@@ -0,0 +1,52 @@
+//! Grouping private rules by the organisation that submitted them
+
+use crate::{PrivateList, Type};
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+impl PrivateList {
+    /// Groups the private rules of this list by their owner
+    ///
+    /// The private section is made up of blocks of rules separated by
+    /// blank lines, each starting with a comment like
+    /// `// GitHub, Inc. : https://github.com` that names the organisation
+    /// operating them. The owner is the text of that first comment up to a
+    /// `: `, if any. Rules in a block without such a comment are skipped.
+    /// Blocks with the same owner are merged. Rules are returned the way
+    /// they are written in the list.
+    ///
+    /// Owners are recorded while the list is parsed and saved along with
+    /// it, so lists built rule by rule have none. Rules that were removed
+    /// since, or that are no longer private, are left out.
+    #[must_use]
+    pub fn by_owner(&self) -> BTreeMap<String, Vec<String>> {
+        let list = &self.0;
+        let mut owners = BTreeMap::<String, Vec<String>>::new();
+        for (owner, rules) in &list.meta.owners {
+            let rules = rules.iter().filter(
+                |rule| matches!(list.rule_info(rule), Some(info) if info.typ == Type::Private),
+            );
+            let mut rules = rules.peekable();
+            if rules.peek().is_some() {
+                owners
+                    .entry(owner.clone())
+                    .or_default()
+                    .extend(rules.cloned());
+            }
+        }
+        owners
+    }
+}
+
+/// The owner named by the comment starting a block of private rules
+pub(crate) fn owner_of(comment: &str) -> String {
+    let comment = comment.trim_start_matches('/').trim();
+    comment
+        .split(": ")
+        .next()
+        .unwrap_or(comment)
+        .trim()
+        .to_owned()
+}
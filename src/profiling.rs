@@ -0,0 +1,52 @@
+//! Counting the work done by lookups
+//!
+//! With the `profiling` feature, every lookup records how many times it
+//! probed the maps of the tree, so the cost of lookups can be measured on
+//! a real mix of hosts. The counters are per thread. Without the feature,
+//! nothing is counted and this module doesn't exist.
+
+use std::cell::Cell;
+
+/// The work done by lookups since the counters were last taken
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Counters {
+    /// The number of times a label was looked up among the children of a node
+    pub lookups: usize,
+    /// The number of those lookups that were for a `*` label after a label
+    /// matched no other child
+    pub wildcard_fallbacks: usize,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            lookups: 0,
+            wildcard_fallbacks: 0,
+        })
+    };
+}
+
+/// Returns the counters of the current thread and resets them
+#[must_use]
+pub fn take_counters() -> Counters {
+    COUNTERS.with(|counters| counters.replace(Counters::default()))
+}
+
+#[inline]
+pub(crate) fn count_lookup() {
+    COUNTERS.with(|counters| {
+        let mut current = counters.get();
+        current.lookups += 1;
+        counters.set(current);
+    });
+}
+
+#[inline]
+pub(crate) fn count_wildcard_fallback() {
+    COUNTERS.with(|counters| {
+        let mut current = counters.get();
+        current.lookups += 1;
+        current.wildcard_fallbacks += 1;
+        counters.set(current);
+    });
+}
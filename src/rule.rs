@@ -0,0 +1,20 @@
+use crate::{Leaf, Type};
+
+/// Information about a rule in the list
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct RuleInfo {
+    /// Whether or not this is an exception rule, i.e. it starts with `!`
+    pub is_exception: bool,
+    /// Whether this is an `ICANN` or a `private` rule
+    pub typ: Type,
+}
+
+impl From<Leaf> for RuleInfo {
+    #[inline]
+    fn from(leaf: Leaf) -> Self {
+        Self {
+            is_exception: leaf.is_exception,
+            typ: leaf.typ,
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! A read-only list backed by a borrowed byte blob
+//!
+//! The blob starts with the 4 byte magic `PSLS` followed by a format
+//! version byte and the number of rules. Then come the offsets of the
+//! rules, plus one past the last, and the rules themselves. Each rule is a
+//! flags byte followed by its labels in reverse order, joined by dots,
+//! e.g. `jp.kawasaki.city`. Rules are sorted label by label, so the rules
+//! sharing their first labels are next to each other. All integers are
+//! 32 bit little endian.
+
+use crate::{Branches, Error, Info, List, Psl, Type, WILDCARD};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+const MAGIC: &[u8; 4] = b"PSLS";
+const VERSION: u8 = 1;
+
+// The magic, the version and the number of rules
+const HEADER_LEN: usize = 9;
+
+const PRIVATE: u8 = 0b01;
+const EXCEPTION: u8 = 0b10;
+
+/// A public suffix list that borrows a pre-built byte blob
+///
+/// Unlike [`List`], this doesn't build a tree of rules. Lookups are binary
+/// searches over the sorted rules in the blob and don't allocate, short of
+/// rules with several `*` labels in a row, so this suits embedded targets where
+/// building the tree at runtime is too costly. Build the blob ahead of time
+/// with [`StaticList::build`] and embed it with `include_bytes!`.
+///
+/// Labels are matched byte for byte, whatever the features of this crate,
+/// so lowercase names in the form the rules are written in. With
+/// `punycode`, rules are stored in both their Unicode and ASCII forms.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct StaticList<'a> {
+    offsets: &'a [u8],
+    rules: &'a [u8],
+    len: usize,
+}
+
+impl<'a> StaticList<'a> {
+    /// Borrows a blob written by [`StaticList::build`]
+    ///
+    /// The whole blob is checked up front, so lookups can trust it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedFormat` if the blob was not written by
+    /// this version of the format and `Error::InvalidList` if it is
+    /// corrupt.
+    pub fn new(blob: &'a [u8]) -> Result<Self, Error> {
+        if blob.len() < HEADER_LEN || &blob[..4] != MAGIC || blob[4] != VERSION {
+            return Err(Error::UnsupportedFormat);
+        }
+        let len = read_u32(blob, 5);
+        let offsets_len = len
+            .checked_add(1)
+            .and_then(|count| count.checked_mul(4))
+            .ok_or(Error::InvalidList)?;
+        let blob = &blob[HEADER_LEN..];
+        if blob.len() < offsets_len {
+            return Err(Error::InvalidList);
+        }
+        let (offsets, rules) = blob.split_at(offsets_len);
+        let list = Self {
+            offsets,
+            rules,
+            len,
+        };
+        list.check()?;
+        Ok(list)
+    }
+
+    /// Writes the rules of `list` as a blob for [`StaticList::new`]
+    ///
+    /// With an [`IcannList`](crate::IcannList) or a
+    /// [`PrivateList`](crate::PrivateList), only the rules of its section
+    /// are written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidList` if the rules don't fit in 4 GiB.
+    pub fn build(list: &List) -> Result<Vec<u8>, Error> {
+        let mut rules = Vec::new();
+        list.walk(|labels, info| {
+            let mut flags = 0;
+            if info.typ == Type::Private {
+                flags |= PRIVATE;
+            }
+            if info.is_exception {
+                flags |= EXCEPTION;
+            }
+            rules.push((flags, labels.join(".")));
+        });
+        rules.sort_unstable_by(|a, b| cmp_keys(a.1.as_bytes(), b.1.as_bytes()));
+
+        let mut blob = Vec::new();
+        blob.extend_from_slice(MAGIC);
+        blob.push(VERSION);
+        blob.extend_from_slice(&len_u32(rules.len())?.to_le_bytes());
+        let mut offset = 0;
+        for (_, key) in &rules {
+            blob.extend_from_slice(&len_u32(offset)?.to_le_bytes());
+            offset += 1 + key.len();
+        }
+        blob.extend_from_slice(&len_u32(offset)?.to_le_bytes());
+        for (flags, key) in &rules {
+            blob.push(*flags);
+            blob.extend_from_slice(key.as_bytes());
+        }
+        Ok(blob)
+    }
+
+    /// The number of rules in the list
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks to see if the list has no rules
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Checks that the offsets, flags and order of the rules are sound
+    fn check(&self) -> Result<(), Error> {
+        let mut end = 0;
+        for i in 0..=self.len {
+            let offset = read_u32(self.offsets, i * 4);
+            // every rule has its flags and at least one label byte
+            let is_sound = if i == 0 {
+                offset == 0
+            } else {
+                matches!(offset.checked_sub(end), Some(len) if len >= 2)
+            };
+            if !is_sound {
+                return Err(Error::InvalidList);
+            }
+            end = offset;
+        }
+        if end != self.rules.len() {
+            return Err(Error::InvalidList);
+        }
+        for i in 0..self.len {
+            let (flags, key) = self.rule(i);
+            if flags & !(PRIVATE | EXCEPTION) != 0
+                || key.split(|x| *x == b'.').any(<[u8]>::is_empty)
+            {
+                return Err(Error::InvalidList);
+            }
+            if i > 0 && cmp_keys(self.rule(i - 1).1, key) != Ordering::Less {
+                return Err(Error::InvalidList);
+            }
+        }
+        Ok(())
+    }
+
+    /// The flags and the labels of rule `i`
+    #[inline]
+    fn rule(&self, i: usize) -> (u8, &'a [u8]) {
+        let start = read_u32(self.offsets, i * 4);
+        let end = read_u32(self.offsets, (i + 1) * 4);
+        let rule = &self.rules[start..end];
+        (rule[0], &rule[1..])
+    }
+
+    /// The label of rule `i` that starts at byte `start` of its labels
+    ///
+    /// Returns `None` if the rule ends before that label, which makes it
+    /// sort before the rules that have one.
+    #[inline]
+    fn label_at(&self, i: usize, start: usize) -> Option<&'a [u8]> {
+        let key = self.rule(i).1;
+        let rest = key.get(start..)?;
+        rest.split(|x| *x == b'.').next()
+    }
+
+    /// Narrows `rules` down to the ones with `label` at byte `start`
+    ///
+    /// Returns the narrowed range and the flags of the rule that ends at
+    /// `label`, if any.
+    #[inline]
+    fn child(
+        &self,
+        (lo, hi): (usize, usize),
+        start: usize,
+        label: &[u8],
+    ) -> Option<((usize, usize), Option<u8>)> {
+        let lo = lo + partition_point(hi - lo, |i| self.label_at(lo + i, start) < Some(label));
+        let hi = lo + partition_point(hi - lo, |i| self.label_at(lo + i, start) <= Some(label));
+        if lo == hi {
+            return None;
+        }
+        // a rule ending at this label sorts before the ones going further
+        let (flags, key) = self.rule(lo);
+        let leaf = if key.len() == start + label.len() {
+            Some(flags)
+        } else {
+            None
+        };
+        Some(((lo, hi), leaf))
+    }
+
+    /// Follows the rules along every path the labels left can take
+    ///
+    /// This is the slow path of `find`, like the one of [`List`], for
+    /// when a label leads both to its own rules and to ones with a `*`.
+    /// `branches` are the rules `label` leads to, the flags of the rule
+    /// ending there and where their next label starts.
+    #[cold]
+    fn find_branches<'b, T>(
+        &self,
+        mut info: Info,
+        mut branches: Branches<((usize, usize), Option<u8>, usize)>,
+        mut label: &'b [u8],
+        mut labels: T,
+        mut len_so_far: usize,
+    ) -> Info
+    where
+        T: Iterator<Item = &'b [u8]>,
+    {
+        loop {
+            let leaves = branches.iter().filter_map(|(_, leaf, _)| leaf);
+            let label_plus_dot = label.len() + 1;
+            if let Some(flags) = leaves.clone().find(|flags| flags & EXCEPTION != 0) {
+                info.typ = Some(typ(flags));
+                info.len = len_so_far;
+                return info;
+            }
+            if let Some(flags) = leaves.clone().next() {
+                info.typ = Some(typ(flags));
+                info.len = len_so_far + label_plus_dot;
+            }
+            len_so_far += label_plus_dot;
+            label = match labels.next() {
+                Some(label) => label,
+                None => return info,
+            };
+
+            let mut next = Branches::new();
+            for (rules, _, start) in branches.iter() {
+                if let Some((node, leaf)) = self.child(rules, start, label) {
+                    next.push((node, leaf, start + label.len() + 1));
+                }
+                if label != WILDCARD.as_bytes() {
+                    if let Some((node, leaf)) = self.child(rules, start, WILDCARD.as_bytes()) {
+                        next.push((node, leaf, start + WILDCARD.len() + 1));
+                    }
+                }
+            }
+            if next.is_empty() {
+                return info;
+            }
+            branches = next;
+        }
+    }
+}
+
+impl Psl for StaticList<'_> {
+    /// Finds the suffix of a domain name given its labels in reverse order
+    ///
+    /// The returned `Info` is the same as the one from the [`List`] the
+    /// blob was built from, for names in the form the rules are written in.
+    fn find<'a, T>(&self, mut labels: T) -> Info
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        let first = match labels.next() {
+            Some(label) => label,
+            None => return Info { len: 0, typ: None },
+        };
+        let mut info = Info {
+            len: first.len(),
+            typ: None,
+        };
+        let (mut rules, leaf) = match self.child((0, self.len), 0, first) {
+            Some(child) => child,
+            None => return info,
+        };
+        info.typ = leaf.map(typ);
+        let mut start = first.len() + 1;
+
+        let mut len_so_far = info.len;
+        while let Some(label) = labels.next() {
+            let (matched, (node, leaf)) = match self.child(rules, start, label) {
+                Some(child) => {
+                    // a `*` next to the label can lead to a longer rule
+                    if label != WILDCARD.as_bytes() {
+                        if let Some(wildcard) = self.child(rules, start, WILDCARD.as_bytes()) {
+                            let mut branches = Branches::new();
+                            branches.push((child.0, child.1, start + label.len() + 1));
+                            branches.push((wildcard.0, wildcard.1, start + WILDCARD.len() + 1));
+                            return self.find_branches(info, branches, label, labels, len_so_far);
+                        }
+                    }
+                    (label, child)
+                }
+                None => match self.child(rules, start, WILDCARD.as_bytes()) {
+                    Some(child) => (WILDCARD.as_bytes(), child),
+                    None => break,
+                },
+            };
+            rules = node;
+            start += matched.len() + 1;
+            let label_plus_dot = label.len() + 1;
+            if let Some(flags) = leaf {
+                info.typ = Some(typ(flags));
+                if flags & EXCEPTION != 0 {
+                    info.len = len_so_far;
+                    break;
+                }
+                info.len = len_so_far + label_plus_dot;
+            }
+            len_so_far += label_plus_dot;
+        }
+
+        info
+    }
+}
+
+#[inline]
+fn typ(flags: u8) -> Type {
+    if flags & PRIVATE != 0 {
+        Type::Private
+    } else {
+        Type::Icann
+    }
+}
+
+/// Compares rules label by label
+#[inline]
+fn cmp_keys(a: &[u8], b: &[u8]) -> Ordering {
+    a.split(|x| *x == b'.').cmp(b.split(|x| *x == b'.'))
+}
+
+/// The number of the first `len` indices for which `pred` holds
+///
+/// `pred` must hold for a prefix of the indices and no others.
+#[inline]
+fn partition_point<F: Fn(usize) -> bool>(len: usize, pred: F) -> usize {
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+#[inline]
+fn read_u32(bytes: &[u8], at: usize) -> usize {
+    let mut int = [0; 4];
+    int.copy_from_slice(&bytes[at..at + 4]);
+    u32::from_le_bytes(int) as usize
+}
+
+fn len_u32(len: usize) -> Result<u32, Error> {
+    u32::try_from(len).map_err(|_| Error::InvalidList)
+}
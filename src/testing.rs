@@ -0,0 +1,49 @@
+//! Reading the official test file of the list
+//!
+//! The list comes with a [test file] of domain names and the registrable
+//! domains they should have. This reads it, so crates building on this
+//! one can run the same checks against their own lookups.
+//!
+//! [test file]: https://raw.githubusercontent.com/publicsuffix/list/master/tests/tests.txt
+
+use alloc::vec::Vec;
+
+/// A line of the official test file
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct TestCase<'a> {
+    /// The domain name to look up, empty for a `null` input
+    pub input: &'a str,
+    /// Its registrable domain, or `None` if it has none
+    pub expected_domain: Option<&'a str>,
+    /// The line of the file the case is on, starting from 1
+    pub line: usize,
+}
+
+/// Reads the test cases in `s`, the contents of the official test file
+///
+/// Each case is a line with the input and the expected registrable domain,
+/// either of which may be `null`. Blank lines and `//` comments are
+/// skipped, and so are lines without an expected domain. Inputs are kept
+/// as written, including their case, so it is up to the caller to
+/// lowercase them if its lookups are case sensitive.
+#[must_use]
+pub fn parse_test_file(s: &str) -> Vec<TestCase<'_>> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with("//"))
+        .filter_map(|(i, line)| {
+            let mut words = line.split_whitespace();
+            let input = words.next()?;
+            let expected = words.next()?;
+            Some(TestCase {
+                input: if input == "null" { "" } else { input },
+                expected_domain: if expected == "null" {
+                    None
+                } else {
+                    Some(expected)
+                },
+                line: i + 1,
+            })
+        })
+        .collect()
+}
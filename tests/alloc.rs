@@ -0,0 +1,92 @@
+use publicsuffix::{labels_bytes, IcannList, List, Psl, StaticList};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::hint::black_box;
+
+// Counts the allocations made by the current thread and the bytes it holds
+struct Counter;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        LIVE_BYTES.with(|bytes| bytes.set(bytes.get() + layout.size() as isize));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.with(|bytes| bytes.set(bytes.get() - layout.size() as isize));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static COUNTER: Counter = Counter;
+
+fn allocations<T, F: FnOnce() -> T>(f: F) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    black_box(f());
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+// The heap bytes held by the value `f` returns
+fn retained_bytes<T, F: FnOnce() -> T>(f: F) -> isize {
+    let before = LIVE_BYTES.with(Cell::get);
+    let value = black_box(f());
+    let bytes = LIVE_BYTES.with(Cell::get) - before;
+    drop(value);
+    bytes
+}
+
+#[test]
+fn lookups_do_not_allocate() {
+    let list: List = include_str!("public_suffix_list.dat").parse().unwrap();
+    for name in [
+        "www.example.co.uk",
+        "WwW.Example.CO.UK",
+        "www.食狮.中国",
+        "a.b.kawasaki.jp",
+        // exceptions next to a `*`, which lookups follow both ways
+        "www.city.kawasaki.jp",
+        "www.ck",
+        "in.futurecms.at",
+    ] {
+        let bytes = name.as_bytes();
+        assert_eq!(allocations(|| list.find(labels_bytes(bytes))), 0);
+        assert_eq!(allocations(|| list.suffix(bytes)), 0);
+        assert_eq!(allocations(|| list.domain(bytes)), 0);
+        assert_eq!(allocations(|| list.lookup(name)), 0);
+        assert_eq!(allocations(|| list.matches_iter(name).count()), 0);
+    }
+}
+
+#[test]
+fn static_lookups_do_not_allocate() {
+    let list: List = include_str!("public_suffix_list.dat").parse().unwrap();
+    let blob = StaticList::build(&list).unwrap();
+    let list = StaticList::new(&blob).unwrap();
+    for name in [
+        "www.example.co.uk",
+        "www.食狮.中国",
+        "a.b.kawasaki.jp",
+        "www.city.kawasaki.jp",
+        "www.ck",
+        "in.futurecms.at",
+    ] {
+        let bytes = name.as_bytes();
+        assert_eq!(allocations(|| list.find(labels_bytes(bytes))), 0);
+        assert_eq!(allocations(|| list.domain(bytes)), 0);
+    }
+}
+
+#[test]
+fn icann_only_lists_are_smaller() {
+    let list = include_str!("public_suffix_list.dat");
+    let icann_only = retained_bytes(|| IcannList::from_str_icann_only(list).unwrap());
+    let icann = retained_bytes(|| IcannList::from(list.parse::<List>().unwrap()));
+    assert!(icann_only * 5 < icann * 4, "{} vs {}", icann_only, icann);
+}
@@ -1,102 +1,92 @@
-use publicsuffix::{List, Psl, Type};
+use publicsuffix::testing::{parse_test_file, TestCase};
+use publicsuffix::{
+    labels, labels_bytes, DynPsl, Error, IcannList, Info, List, ListBuilder, MatchPolicy, Outcome,
+    OwnedDomain, PrivateList, Psl, RuleKind, SharedList, StaticList, Type,
+};
 use rspec::report::ExampleResult;
+use std::borrow::Cow;
 use std::sync::LazyLock;
-use std::{env, mem, str};
+use std::{env, iter, str};
 
 static LIST: LazyLock<List> =
     LazyLock::new(|| include_str!("public_suffix_list.dat").parse().unwrap());
 
 #[test]
-fn list_behaviour() {
+fn official_tests() {
     rspec::run(&rspec::describe("the official test", (), |ctx| {
-        let mut parse = false;
-
         // `tests.txt` was downloaded from
         // https://raw.githubusercontent.com/publicsuffix/list/master/tests/tests.txt
-        for (i, line) in include_str!("tests.txt").lines().enumerate() {
-            match line {
-                line if line.trim().is_empty() => {
-                    parse = true;
-                    continue;
-                }
-                line if line.starts_with("//") => {
-                    continue;
-                }
-                line => {
-                    if !parse {
-                        continue;
-                    }
-                    let mut test = line.split_whitespace().peekable();
-                    if test.peek().is_none() {
-                        continue;
-                    }
-                    let input = match test.next() {
-                        Some("null") => "",
-                        Some(res) => res,
-                        None => panic!("line {} of the test file doesn't seem to be valid", i),
-                    };
-                    if !expected_tld(input)
-                        || (cfg!(not(feature = "punycode")) && input.contains("xn--"))
-                    {
-                        continue;
-                    }
-                    let (expected_root, expected_suffix) = match test.next() {
-                        Some("null") => (None, None),
-                        Some(root) => {
-                            let suffix = {
-                                let parts: Vec<&str> = root.split('.').rev().collect();
-                                (&parts[..parts.len() - 1])
-                                    .iter()
-                                    .rev()
-                                    .map(|part| *part)
-                                    .collect::<Vec<_>>()
-                                    .join(".")
-                            };
-                            (Some(root.to_string()), Some(suffix.to_string()))
-                        }
-                        None => panic!("line {} of the test file doesn't seem to be valid", i),
-                    };
-                    let (found_root, found_suffix) =
-                        if input.starts_with(".") || input.contains("..") {
-                            (None, None)
+        for TestCase {
+            input,
+            expected_domain,
+            line,
+        } in parse_test_file(include_str!("tests.txt"))
+        {
+            if !expected_tld(input) || (cfg!(not(feature = "punycode")) && input.contains("xn--")) {
+                continue;
+            }
+            let expected_root = expected_domain.map(str::to_string);
+            let expected_suffix = expected_domain.map(|root| {
+                let parts: Vec<&str> = root.split('.').rev().collect();
+                parts[..parts.len() - 1]
+                    .iter()
+                    .rev()
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(".")
+            });
+            let (found_root, found_suffix) = if input.starts_with(".") || input.contains("..") {
+                (None, None)
+            } else {
+                LIST.domain(input.to_lowercase().as_bytes())
+                    .map(|d| {
+                        let domain = str::from_utf8(d.as_bytes()).unwrap().to_string();
+                        let suffix = str::from_utf8(d.suffix().as_bytes()).unwrap().to_string();
+                        (Some(domain), Some(suffix))
+                    })
+                    .unwrap_or((None, None))
+            };
+            ctx.when(msg(format!("input is `{}`", input)), |ctx| {
+                let full_domain = expected_root.is_some();
+
+                ctx.it(
+                    msg(format!("means the root domain {}", val(&expected_root))),
+                    move |_| {
+                        if expected_root == found_root {
+                            ExampleResult::Success
                         } else {
-                            LIST.domain(input.to_lowercase().as_bytes())
-                                .map(|d| {
-                                    let domain = str::from_utf8(d.as_bytes()).unwrap().to_string();
-                                    let suffix =
-                                        str::from_utf8(d.suffix().as_bytes()).unwrap().to_string();
-                                    (Some(domain), Some(suffix))
-                                })
-                                .unwrap_or((None, None))
-                        };
-                    ctx.when(msg(format!("input is `{}`", input)), |ctx| {
-                        let full_domain = expected_root.is_some();
-
-                        ctx.it(msg(format!("means the root domain {}", val(&expected_root))), move |_| {
-                            if expected_root == found_root {
+                            let msg = format!(
+                                "expected `{:?}` but found `{:?}` on line {} of `tests.txt`",
+                                expected_root, found_root, line
+                            );
+                            ExampleResult::Failure(Some(msg))
+                        }
+                    },
+                );
+
+                if full_domain {
+                    ctx.it(
+                        msg(format!("also means the suffix {}", val(&expected_suffix))),
+                        move |_| {
+                            if expected_suffix == found_suffix {
                                 ExampleResult::Success
                             } else {
-                                let msg = format!("expected `{:?}` but found `{:?}` on line {} of `test_psl.txt`", expected_root, found_root, i+1);
+                                let msg = format!(
+                                    "expected `{:?}` but found `{:?}` on line {} of `tests.txt`",
+                                    expected_suffix, found_suffix, line
+                                );
                                 ExampleResult::Failure(Some(msg))
                             }
-                        });
-
-                        if full_domain {
-                            ctx.it(msg(format!("also means the suffix {}", val(&expected_suffix))), move |_| {
-                                if expected_suffix == found_suffix {
-                                    ExampleResult::Success
-                                } else {
-                                    let msg = format!("expected `{:?}` but found `{:?}` on line {} of `test_psl.txt`", expected_suffix, found_suffix, i+1);
-                                    ExampleResult::Failure(Some(msg))
-                                }
-                            });
-                        }
-                    });
+                        },
+                    );
                 }
-            }
+            });
         }
     }));
+}
 
+#[test]
+fn list_behaviour() {
     rspec::run(&rspec::describe("suffix tests", (), |ctx| {
         let extra = vec![
             (
@@ -105,8 +95,10 @@ fn list_behaviour() {
             ),
             ("yokohama.jp", "jp"),
             ("kobe.jp", "jp"),
-            #[cfg(feature = "anycase")]
+            #[cfg(any(feature = "anycase", feature = "ascii-case"))]
             ("foo.bar.platformsh.Site", "bar.platformsh.Site"),
+            #[cfg(any(feature = "anycase", feature = "ascii-case"))]
+            ("Foo.CO.UK", "CO.UK"),
             ("bar.platformsh.site", "bar.platformsh.site"),
             ("platform.sh", "sh"),
             ("sh", "sh"),
@@ -200,29 +192,1848 @@ fn list_behaviour() {
     }));
 }
 
+#[test]
+fn test_file() {
+    let cases = parse_test_file(
+        "// Any copyright is dedicated to the Public Domain.
+
+        // null input.
+        null null
+        // Mixed case.
+        example.COM example.com
+          // Unlisted TLD.
+        example null
+        truncated
+        ",
+    );
+    let case = |input, expected_domain, line| TestCase {
+        input,
+        expected_domain,
+        line,
+    };
+    assert_eq!(
+        cases,
+        [
+            case("", None, 4),
+            case("example.COM", Some("example.com"), 6),
+            case("example", None, 8),
+        ]
+    );
+    assert!(parse_test_file(include_str!("tests.txt")).len() > 70);
+}
+
+#[test]
+fn suffix_label_count() {
+    assert_eq!(LIST.suffix_label_count("foo.bar.platformsh.site"), Some(3));
+    assert_eq!(LIST.suffix_label_count("example.co.uk"), Some(2));
+    assert_eq!(LIST.suffix_label_count("example.com"), Some(1));
+    assert_eq!(LIST.suffix_label_count("www.city.kawasaki.jp"), Some(2));
+}
+
+#[test]
+fn lookup() {
+    let found = LIST.lookup("www.example.co.uk").unwrap();
+    assert_eq!(found.suffix(), "co.uk");
+    assert_eq!(found.registrable_domain(), Some("example.co.uk"));
+    assert_eq!(found.typ(), Some(Type::Icann));
+    assert!(found.is_known());
+    assert!(!found.is_exception());
+    assert!(!found.is_wildcard_match());
+    assert!(!found.is_implicit_wildcard());
+
+    let found = LIST.lookup("foo.bar.platformsh.site.").unwrap();
+    assert_eq!(found.suffix(), "bar.platformsh.site.");
+    assert_eq!(found.registrable_domain(), Some("foo.bar.platformsh.site."));
+    assert_eq!(found.typ(), Some(Type::Private));
+    assert!(found.is_wildcard_match());
+    assert!(!found.is_implicit_wildcard());
+
+    let found = LIST.lookup("www.city.kawasaki.jp").unwrap();
+    assert_eq!(found.suffix(), "kawasaki.jp");
+    assert_eq!(found.registrable_domain(), Some("city.kawasaki.jp"));
+    assert!(found.is_exception());
+
+    let found = LIST.lookup("example.gafregsrse").unwrap();
+    assert_eq!(found.suffix(), "gafregsrse");
+    assert_eq!(found.registrable_domain(), Some("example.gafregsrse"));
+    assert!(!found.is_known());
+    assert!(!found.is_wildcard_match());
+    assert!(found.is_implicit_wildcard());
+
+    assert_eq!(LIST.lookup("co.uk").unwrap().registrable_domain(), None);
+
+    let found = LIST.lookup("foo.github.io.").unwrap();
+    assert_eq!(found.icann_suffix(), Some("io."));
+    assert_eq!(found.private_suffix(), Some("github.io."));
+    let found = LIST.lookup("www.city.kawasaki.jp").unwrap();
+    assert_eq!(found.icann_suffix(), Some("kawasaki.jp"));
+    assert_eq!(found.private_suffix(), None);
+    let found = LIST.lookup("example.gafregsrse").unwrap();
+    assert_eq!((found.icann_suffix(), found.private_suffix()), (None, None));
+    let icann = IcannList::from(LIST.clone());
+    let found = icann.as_ref().lookup("foo.github.io").unwrap();
+    assert_eq!(found.suffix(), "io");
+    assert_eq!(found.private_suffix(), None);
+    assert!(LIST.lookup("").is_none());
+}
+
+#[test]
+#[cfg(not(any(feature = "anycase", feature = "ascii-case")))]
+fn case_sensitive_by_default() {
+    // names are not lowercased before they are matched
+    assert_eq!(LIST.suffix(b"www.example.CO.UK").unwrap(), "UK");
+    assert_eq!(LIST.lookup("Foo.Co.Uk").unwrap().typ(), None);
+    assert!(LIST.rule_info("co.uk").is_some());
+    assert!(LIST.rule_info("CO.UK").is_none());
+
+    // and neither are rules, so case significant labels stay apart
+    let list = "// ===BEGIN PRIVATE DOMAINS===\nCorp.Internal\nlab.internal"
+        .parse::<List>()
+        .unwrap();
+    let suffix = |name: &str| list.lookup(name).unwrap().suffix().to_owned();
+    assert_eq!(suffix("a.Corp.Internal"), "Corp.Internal");
+    assert_eq!(suffix("a.corp.internal"), "internal");
+    assert_eq!(suffix("a.CORP.Internal"), "Internal");
+    assert_eq!(suffix("a.lab.internal"), "lab.internal");
+    assert_eq!(suffix("a.Lab.internal"), "internal");
+    assert!(list.rule_info("corp.internal").is_none());
+}
+
+#[test]
+#[cfg(all(feature = "ascii-case", not(feature = "anycase")))]
+fn ascii_case() {
+    let list = "// ===BEGIN ICANN DOMAINS===\nfoo.com\nbüro\nCorp.Internal"
+        .parse::<List>()
+        .unwrap();
+    let suffix = |name: &str| list.lookup(name).unwrap().suffix().to_owned();
+    assert_eq!(suffix("a.Foo.COM"), "Foo.COM");
+    assert_eq!(suffix("a.foo.com"), "foo.com");
+    assert_eq!(suffix("a.corp.INTERNAL"), "corp.INTERNAL");
+    // only ASCII letters are folded
+    assert_eq!(suffix("a.BüRO"), "BüRO");
+    assert_eq!(suffix("a.BÜRO"), "BÜRO");
+    assert_eq!(list.lookup("a.BÜRO").unwrap().typ(), None);
+    assert!(list.rule_info("corp.internal").is_some());
+    assert_eq!(
+        list.matching_rule("a.CORP.internal").as_deref(),
+        Some("corp.internal")
+    );
+
+    // labels too long for the stack are folded too
+    let long = "A".repeat(70);
+    let list = format!("// ===BEGIN ICANN DOMAINS===\n{}.com", long.to_lowercase())
+        .parse::<List>()
+        .unwrap();
+    assert_eq!(
+        list.lookup(&format!("x.{}.com", long)).unwrap().typ(),
+        Some(Type::Icann)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "anycase", feature = "ascii-case"))]
+fn case_is_preserved() {
+    let found = LIST.lookup("Foo.CO.UK").unwrap();
+    assert_eq!(found.suffix(), "CO.UK");
+    assert_eq!(found.registrable_domain(), Some("Foo.CO.UK"));
+    assert_eq!(LIST.suffix(b"Foo.CO.UK").unwrap().as_bytes(), b"CO.UK");
+    assert_eq!(LIST.domain(b"Foo.CO.UK").unwrap().as_bytes(), b"Foo.CO.UK");
+    // the rule is returned the way it is written in the list
+    assert_eq!(LIST.matching_rule("Foo.CO.UK").as_deref(), Some("co.uk"));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn save_and_load() {
+    let mut bytes = Vec::new();
+    LIST.save(&mut bytes).unwrap();
+    assert!(bytes.len() < include_bytes!("public_suffix_list.dat").len());
+    assert_eq!(List::load(&bytes[..]).unwrap(), *LIST);
+
+    let mut other = Vec::new();
+    LIST.save(&mut other).unwrap();
+    assert_eq!(bytes, other);
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[4] += 1;
+    assert_eq!(
+        List::load(&wrong_version[..]),
+        Err(Error::UnsupportedFormat)
+    );
+    assert_eq!(
+        List::load(&include_bytes!("public_suffix_list.dat")[..]),
+        Err(Error::UnsupportedFormat)
+    );
+    // a cut short file is corrupt, not a failed read
+    for len in [0, 3, bytes.len() / 2, bytes.len() - 1] {
+        assert_eq!(
+            List::load(&bytes[..len]),
+            Err(Error::InvalidList),
+            "{}",
+            len
+        );
+    }
+
+    // the section, length limit and underscore setting aren't saved
+    let mut strict = Vec::new();
+    let list = IcannList::from(LIST.clone().with_underscores(false));
+    assert_eq!(
+        list.as_ref().suffix_type("foo.github.io"),
+        Some(Type::Icann)
+    );
+    list.as_ref().save(&mut strict).unwrap();
+    let loaded = List::load(&strict[..]).unwrap();
+    assert!(loaded.allows_underscores());
+    assert_eq!(loaded.suffix_type("foo.github.io"), Some(Type::Private));
+}
+
+#[test]
+fn matching_rule() {
+    let rule = |input| LIST.matching_rule(input);
+    assert_eq!(
+        rule("foo.bar.kawasaki.jp").as_deref(),
+        Some("*.kawasaki.jp")
+    );
+    assert_eq!(
+        rule("www.city.kawasaki.jp").as_deref(),
+        Some("!city.kawasaki.jp")
+    );
+    assert_eq!(rule("www.example.co.uk.").as_deref(), Some("co.uk"));
+    assert_eq!(
+        rule("foo.bar.platformsh.site").as_deref(),
+        Some("*.platformsh.site")
+    );
+    assert_eq!(rule("example.gafregsrse").as_deref(), Some("*"));
+    assert_eq!(rule(""), None);
+}
+
+#[test]
+fn normalize_input() {
+    for name in ["www.example.co.uk.", "www.食狮.中国", "a.b.kawasaki.jp", ""] {
+        let normalized = LIST.normalize_input(name).unwrap();
+        assert_eq!(
+            LIST.suffix(normalized.as_bytes())
+                .map(|suffix| suffix.typ()),
+            LIST.suffix(name.as_bytes()).map(|suffix| suffix.typ()),
+            "{}",
+            name
+        );
+    }
+    let normalize = |name| LIST.normalize_input(name).unwrap();
+    assert!(matches!(normalize("www.example.com"), Cow::Borrowed(_)));
+    #[cfg(feature = "punycode")]
+    {
+        assert_eq!(normalize("WwW.Example.COM."), "www.example.com.");
+        let upper = normalize("WWW.EXAMPLE.CO.UK");
+        assert_eq!(
+            LIST.suffix(upper.as_bytes()).unwrap().typ(),
+            Some(Type::Icann)
+        );
+        assert_eq!(normalize("www.食狮.中国"), "www.xn--85x722f.xn--fiqs8s");
+    }
+    #[cfg(not(feature = "punycode"))]
+    assert_eq!(normalize("www.食狮.中国"), "www.食狮.中国");
+}
+
+#[test]
+fn split() {
+    assert_eq!(
+        LIST.split("www.example.co.uk"),
+        Some(("example.co.uk", "co.uk"))
+    );
+    assert_eq!(LIST.split("example.com."), Some(("example.com.", "com.")));
+    assert_eq!(
+        LIST.split("www.city.kawasaki.jp"),
+        Some(("city.kawasaki.jp", "kawasaki.jp"))
+    );
+    assert_eq!(LIST.split("co.uk"), None);
+    assert_eq!(LIST.split(""), None);
+    for name in [
+        "www.example.co.uk",
+        "a.b.foo.github.io",
+        "example.gafregsrse",
+    ] {
+        let (domain, suffix) = LIST.split(name).unwrap();
+        assert_eq!(
+            LIST.domain(name.as_bytes()).unwrap().as_bytes(),
+            domain.as_bytes()
+        );
+        assert_eq!(
+            LIST.suffix(name.as_bytes()).unwrap().as_bytes(),
+            suffix.as_bytes()
+        );
+    }
+}
+
+#[test]
+fn find_host_bytes() {
+    let icann = |len| Info {
+        len,
+        typ: Some(Type::Icann),
+    };
+    assert_eq!(LIST.find_host_bytes(b"www.example.co.uk"), icann(5));
+    assert_eq!(LIST.find_host_bytes(b"\xff\xfe.example.co.uk."), icann(5));
+    assert_eq!(LIST.find_host_bytes(b"www.\xc3.kawasaki.jp"), icann(13));
+    assert_eq!(
+        LIST.find_host_bytes(b"example.\xff"),
+        Info { len: 1, typ: None }
+    );
+    assert_eq!(LIST.find_host_bytes(b""), Info { len: 0, typ: None });
+    #[cfg(feature = "std")]
+    assert_eq!(
+        LIST.find_host_bytes(b"127.0.0.1"),
+        Info { len: 0, typ: None }
+    );
+}
+
+#[test]
+fn suffix_into() {
+    let mut buf = [0; 8];
+    assert_eq!(LIST.suffix_into(b"www.example.co.uk", &mut buf), Some(5));
+    assert_eq!(&buf[..5], b"co.uk");
+    assert_eq!(LIST.suffix_into(b"www.example.co.uk.", &mut buf), Some(6));
+    assert_eq!(&buf[..6], b"co.uk.");
+
+    // a buffer too small is left untouched
+    let mut buf = [0; 8];
+    assert_eq!(LIST.suffix_into(b"foo.b.kawasaki.jp", &mut buf), None);
+    assert_eq!(buf, [0; 8]);
+    assert_eq!(LIST.suffix_into(b"", &mut buf), None);
+    assert_eq!(LIST.suffix_into(b"com", &mut [0; 3]), Some(3));
+}
+
+#[test]
+fn cookie_domain() {
+    assert_eq!(LIST.domain(b"foo.github.io").unwrap(), "foo.github.io");
+    assert_eq!(LIST.cookie_domain("foo.github.io"), Some("github.io"));
+    assert_eq!(LIST.cookie_domain("a.b.foo.github.io"), Some("github.io"));
+    assert_eq!(LIST.cookie_domain("github.io"), Some("github.io"));
+    assert_eq!(
+        LIST.cookie_domain("www.example.co.uk."),
+        Some("example.co.uk.")
+    );
+    assert_eq!(
+        LIST.cookie_domain("example.gafregsrse"),
+        Some("example.gafregsrse")
+    );
+    assert_eq!(LIST.cookie_domain("co.uk"), None);
+    assert_eq!(LIST.cookie_domain(""), None);
+    // private lists still use the ICANN rules
+    let private = PrivateList::from(LIST.clone());
+    assert_eq!(
+        private.as_ref().cookie_domain("foo.github.io"),
+        Some("github.io")
+    );
+}
+
+#[test]
+fn find_batch() {
+    let hosts = [
+        "www.example.co.uk",
+        "example.com.",
+        "example.gafregsrse",
+        "",
+    ];
+    let expected = hosts
+        .iter()
+        .map(|host| LIST.find(labels_bytes(host.as_bytes())))
+        .collect::<Vec<_>>();
+    assert_eq!(LIST.find_batch(hosts), expected);
+    assert_eq!(
+        expected[1],
+        Info {
+            len: 3,
+            typ: Some(Type::Icann)
+        }
+    );
+    assert!(LIST.find_batch(iter::empty()).is_empty());
+}
+
+#[test]
+fn parse_domain() {
+    let domain = |input| LIST.parse_domain(input).map(|domain| domain.as_bytes());
+    assert_eq!(domain("www.example.co.uk"), Ok(&b"example.co.uk"[..]));
+    assert_eq!(domain("www.example.com."), Ok(&b"example.com."[..]));
+    assert_eq!(
+        domain("xn--d1acufc.xn--p1ai"),
+        Ok(&b"xn--d1acufc.xn--p1ai"[..])
+    );
+    assert_eq!(domain("a-b.c9.com"), Ok(&b"c9.com"[..]));
+    assert_eq!(domain("127.com"), Ok(&b"127.com"[..]));
+    assert_eq!(domain("www.127.0.0.com"), Ok(&b"0.com"[..]));
+    #[cfg(feature = "punycode")]
+    assert_eq!(domain("www.食狮.中国"), Ok("食狮.中国".as_bytes()));
+
+    let long_label = "a".repeat(64);
+    let long_domain = ["a".repeat(63).as_str(); 4].join(".");
+    for (input, error) in [
+        (
+            "-example.com",
+            Error::LabelStartsWithHyphen as fn(String) -> Error,
+        ),
+        ("example-.com", Error::LabelEndsWithHyphen),
+        ("exa/mple.com", Error::InvalidLabelChar),
+        ("exa mple.com", Error::InvalidLabelChar),
+        ("www..example.com", Error::EmptyDomainLabel),
+        ("", Error::EmptyDomainLabel),
+        (".", Error::EmptyDomainLabel),
+        ("example.123", Error::NumericTld),
+        ("example.127.", Error::NumericTld),
+        ("127.38.53.247", Error::IpAddress),
+        ("127.0.0.1.", Error::IpAddress),
+        ("127.0.0.256", Error::NumericTld),
+        ("1.127.0.0.1", Error::NumericTld),
+        (&long_label, Error::LabelTooLong),
+        ("co.uk", Error::NoRegistrableDomain),
+        ("com", Error::NoRegistrableDomain),
+    ] {
+        assert_eq!(
+            LIST.parse_domain(input),
+            Err(error(input.to_string())),
+            "{}",
+            input
+        );
+    }
+
+    assert_eq!(
+        LIST.parse_domain(&long_domain),
+        Err(Error::InputTooLong { len: 255, max: 253 })
+    );
+
+    assert!(LIST.allows_underscores());
+    assert_eq!(domain("_tcp.example.com."), Ok(&b"example.com."[..]));
+    assert_eq!(domain("_sip._udp.example.com"), Ok(&b"example.com"[..]));
+    let strict = LIST.clone().with_underscores(false);
+    assert!(!strict.allows_underscores());
+    for input in ["_tcp.example.com.", "a_b.example.com"] {
+        assert_eq!(
+            strict.parse_domain(input),
+            Err(Error::InvalidLabelChar(input.to_string()))
+        );
+    }
+    // finding the suffix doesn't care either way
+    assert_eq!(strict.suffix(b"_tcp.example.com.").unwrap(), "com.");
+    let error = LIST
+        .clone()
+        .with_max_input_len(255)
+        .parse_domain(&long_domain)
+        .unwrap_err();
+    assert_eq!(error, Error::DomainTooLong(long_domain.clone()));
+    assert_eq!(
+        error.to_string(),
+        format!("domain `{}` is too long to be a DNS name", long_domain)
+    );
+}
+
+#[test]
+fn domain_owned() {
+    let domain = {
+        let input = String::from("www.example.co.uk");
+        LIST.domain_owned(&input).unwrap()
+    };
+    assert_eq!(domain.as_str(), "example.co.uk");
+    assert_eq!(domain.suffix(), "co.uk");
+    assert_eq!(domain.typ(), Some(Type::Icann));
+    assert_eq!(LIST.domain(b"www.example.co.uk").unwrap(), domain.as_str());
+
+    let private = LIST.domain_owned("a.foo.github.io.").unwrap();
+    assert_eq!(private.as_str(), "foo.github.io.");
+    assert_eq!(private.suffix(), "github.io.");
+    assert_eq!(private.typ(), Some(Type::Private));
+    let unknown = LIST.domain_owned("www.example.gafregsrse").unwrap();
+    assert_eq!(unknown.suffix(), "gafregsrse");
+    assert_eq!(unknown.typ(), None);
+    assert_eq!(LIST.domain_owned("co.uk"), None);
+    assert_eq!(LIST.domain_owned(""), None);
+
+    let mut cache = std::collections::HashMap::<OwnedDomain, usize>::new();
+    for host in ["www.example.co.uk", "mail.example.co.uk", "example.com"] {
+        *cache.entry(LIST.domain_owned(host).unwrap()).or_default() += 1;
+    }
+    assert_eq!(cache[&domain], 2);
+    assert_eq!(domain.clone().into_string(), "example.co.uk");
+}
+
+#[test]
+fn validate_registrable() {
+    assert_eq!(
+        LIST.validate_registrable("example.co.uk"),
+        Ok(("example.co.uk", "co.uk"))
+    );
+    assert_eq!(
+        LIST.validate_registrable("example.co.uk."),
+        Ok(("example.co.uk.", "co.uk."))
+    );
+    assert_eq!(
+        LIST.validate_registrable("foo.github.io"),
+        Ok(("foo.github.io", "github.io"))
+    );
+    assert_eq!(
+        LIST.validate_registrable("example.gafregsrse"),
+        Ok(("example.gafregsrse", "gafregsrse"))
+    );
+    assert_eq!(
+        LIST.validate_registrable("www.example.co.uk"),
+        Err(Error::HasSubdomains("www.example.co.uk".into()))
+    );
+    assert_eq!(LIST.validate_registrable("127.com"), Ok(("127.com", "com")));
+    assert_eq!(
+        LIST.validate_registrable("example.127"),
+        Err(Error::NumericTld("example.127".into()))
+    );
+    assert_eq!(
+        LIST.validate_registrable("127.38.53.247"),
+        Err(Error::IpAddress("127.38.53.247".into()))
+    );
+    assert_eq!(
+        LIST.validate_registrable("co.uk"),
+        Err(Error::NoRegistrableDomain("co.uk".into()))
+    );
+    assert_eq!(
+        LIST.validate_registrable(""),
+        Err(Error::EmptyDomainLabel("".into()))
+    );
+    assert_eq!(
+        LIST.validate_registrable("example..uk"),
+        Err(Error::EmptyDomainLabel("example..uk".into()))
+    );
+}
+
+#[test]
+fn max_input_len() {
+    let huge = format!("{}.com", "a.".repeat(512 * 1024));
+    assert!(LIST.lookup(&huge).is_none());
+    assert!(LIST.split(&huge).is_none());
+    assert_eq!(LIST.suffix_type(&huge), None);
+    assert_eq!(LIST.matching_rule(&huge), None);
+    assert_eq!(LIST.outcome(&huge), Outcome::Empty);
+    assert_eq!(LIST.matches_iter(&huge).count(), 0);
+    assert_eq!(LIST.find_host_bytes(huge.as_bytes()).len, 0);
+    assert_eq!(
+        LIST.normalize_input(&huge),
+        Err(Error::InputTooLong {
+            len: huge.len(),
+            max: 253
+        })
+    );
+
+    // the limit doesn't count a trailing dot
+    let longest = format!("{}.com", "a".repeat(249));
+    assert_eq!(LIST.max_input_len(), 253);
+    assert_eq!(LIST.lookup(&longest).map(|m| m.suffix()), Some("com"));
+    let fqdn = format!("{}.", longest);
+    assert_eq!(LIST.lookup(&fqdn).map(|m| m.suffix()), Some("com."));
+    assert!(LIST.lookup(&format!("a{}", longest)).is_none());
+
+    let list = LIST.clone().with_max_input_len(huge.len());
+    assert_eq!(list.lookup(&huge).map(|m| m.suffix()), Some("com"));
+    let list = LIST.clone().with_max_input_len(11);
+    assert!(list.lookup("www.example.com").is_none());
+    assert!(list.lookup("example.com").is_some());
+}
+
+#[test]
+fn outcome() {
+    assert_eq!(LIST.outcome("www.example.co.uk"), Outcome::KnownSuffix);
+    assert_eq!(LIST.outcome("www.city.kawasaki.jp"), Outcome::KnownSuffix);
+    assert_eq!(
+        LIST.outcome("example.gafregsrse"),
+        Outcome::ImplicitWildcard
+    );
+    assert_eq!(LIST.outcome("gafregsrse"), Outcome::ImplicitWildcard);
+    assert_eq!(LIST.outcome(""), Outcome::Empty);
+    assert_eq!(LIST.outcome("."), Outcome::Empty);
+    assert_eq!(LIST.outcome("example.com.."), Outcome::Empty);
+
+    assert_eq!(LIST.find(iter::empty()), Info { len: 0, typ: None });
+    assert_eq!(
+        LIST.find("example.gafregsrse".rsplit('.').map(str::as_bytes)),
+        Info { len: 10, typ: None }
+    );
+}
+
+#[test]
+fn labels_in_match_order() {
+    let collect = |host| labels(host).collect::<Vec<_>>();
+    assert_eq!(collect("www.example.com"), ["com", "example", "www"]);
+    assert_eq!(collect("www.example.com."), ["com", "example", "www"]);
+    assert_eq!(collect("com"), ["com"]);
+    assert_eq!(collect(""), [""]);
+    assert_eq!(collect("."), [""]);
+    assert_eq!(collect("example..com"), ["com", "", "example"]);
+    assert_eq!(collect(".com."), ["com", ""]);
+    assert_eq!(collect("example.com.."), ["", "com", "example"]);
+    assert_eq!(
+        labels_bytes(b"\xff.example.com.").collect::<Vec<_>>(),
+        [&b"com"[..], b"example", b"\xff"]
+    );
+
+    assert_eq!(
+        LIST.find(labels_bytes(b"www.example.co.uk.")),
+        LIST.find(labels("www.example.co.uk").map(str::as_bytes))
+    );
+    assert_eq!(LIST.find(labels_bytes(b"")), Info { len: 0, typ: None });
+}
+
+#[test]
+#[cfg(feature = "profiling")]
+fn profiling() {
+    use publicsuffix::profiling::{take_counters, Counters};
+
+    let _ = take_counters();
+    let counters = |lookups, wildcard_fallbacks| Counters {
+        lookups,
+        wildcard_fallbacks,
+    };
+    LIST.find(labels_bytes(b"www.example.com"));
+    assert_eq!(take_counters(), counters(2, 0));
+    LIST.find(labels_bytes(b"a.b.foo.bar.platformsh.site"));
+    assert_eq!(take_counters(), counters(5, 1));
+    LIST.find(labels_bytes(b"gafregsrse"));
+    assert_eq!(take_counters(), counters(1, 0));
+    assert_eq!(take_counters(), counters(0, 0));
+}
+
+#[test]
+fn single_label() {
+    let find = |list: &List, name: &str| list.find(iter::once(name.as_bytes()));
+    let info = |len, typ| Info { len, typ };
+    assert_eq!(find(&LIST, "com"), info(3, Some(Type::Icann)));
+    assert_eq!(find(&LIST, "localhost"), info(9, None));
+    assert_eq!(find(&LIST, ""), info(0, None));
+
+    // a label that only leads to other rules is matched by the implicit `*`
+    let list = "// ===BEGIN ICANN DOMAINS===\n*.ck\n!www.ck"
+        .parse::<List>()
+        .unwrap();
+    assert_eq!(find(&list, "ck"), info(2, None));
+    assert_eq!(list.outcome("ck"), Outcome::ImplicitWildcard);
+    assert_eq!(find(&list, "www"), info(3, None));
+}
+
+#[test]
+fn try_find() {
+    let info = |len, typ| Ok(Info { len, typ });
+    assert_eq!(
+        LIST.try_find("www.example.co.uk"),
+        info(5, Some(Type::Icann))
+    );
+    assert_eq!(
+        LIST.try_find("www.example.co.uk."),
+        info(5, Some(Type::Icann))
+    );
+    assert_eq!(LIST.try_find("example.gafregsrse"), info(10, None));
+    #[cfg(feature = "std")]
+    assert_eq!(LIST.try_find("127.0.0.1"), info(0, None));
+    for host in ["", ".", "example..com", ".example.com", "example.com.."] {
+        assert_eq!(
+            LIST.try_find(host),
+            Err(Error::EmptyDomainLabel(host.to_owned())),
+            "{}",
+            host
+        );
+    }
+    let long = "a.".repeat(200);
+    assert_eq!(
+        LIST.try_find(&long),
+        Err(Error::InputTooLong { len: 400, max: 253 })
+    );
+}
+
+#[test]
+fn find_with_policy() {
+    let find = |host, policy| LIST.find_with_policy(host, policy);
+    let info = |len, typ| Info { len, typ };
+    let icann = info(2, Some(Type::Icann));
+    let private = info(9, Some(Type::Private));
+    assert_eq!(find("foo.github.io", MatchPolicy::IcannOnly), icann);
+    assert_eq!(find("foo.github.io", MatchPolicy::PrivateOnly), private);
+    assert_eq!(find("foo.github.io", MatchPolicy::LongestOverall), private);
+    assert_eq!(find("foo.github.io", MatchPolicy::IcannPreferred), icann);
+
+    // `private` rules still decide when there is no `ICANN` one
+    let list = "// ===BEGIN PRIVATE DOMAINS===\nexample.internal"
+        .parse::<List>()
+        .unwrap();
+    assert_eq!(
+        list.find_with_policy("foo.example.internal", MatchPolicy::IcannPreferred),
+        info(16, Some(Type::Private))
+    );
+    assert_eq!(
+        list.find_with_policy("foo.example.internal", MatchPolicy::IcannOnly),
+        info(8, None)
+    );
+
+    // the policy takes the place of the section the list keeps to
+    let icann_list = IcannList::from(LIST.clone());
+    assert_eq!(
+        icann_list
+            .as_ref()
+            .find_with_policy("foo.github.io", MatchPolicy::LongestOverall),
+        private
+    );
+    assert_eq!(find("", MatchPolicy::IcannPreferred), info(0, None));
+}
+
+#[test]
+fn find_ambiguities() {
+    assert!(LIST.find_ambiguities().is_empty());
+
+    let list = "// ===BEGIN ICANN DOMAINS===
+        *.foo
+        !www.foo
+        baz.foo
+        // ===BEGIN PRIVATE DOMAINS===
+        bar.foo
+        *.baz.foo"
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .parse::<List>()
+        .unwrap();
+    assert_eq!(list.find_ambiguities(), ["bar.foo"]);
+    assert_eq!(list.suffix_type("a.bar.foo"), Some(Type::Private));
+    assert_eq!(list.suffix(b"a.bar.foo").unwrap(), "bar.foo");
+    assert!(IcannList::from(list.clone())
+        .as_ref()
+        .find_ambiguities()
+        .is_empty());
+}
+
+#[test]
+fn dangling_exceptions() {
+    assert!(LIST.dangling_exceptions().is_empty());
+
+    let list = "// ===BEGIN ICANN DOMAINS===
+        *.kawasaki.jp
+        !city.kawasaki.jp
+        *.jp
+        !www.sub.jp
+        !orphan.example.uk
+        // ===BEGIN PRIVATE DOMAINS===
+        !other.kawasaki.jp"
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .parse::<List>()
+        .unwrap();
+    assert_eq!(
+        list.dangling_exceptions(),
+        ["!other.kawasaki.jp", "!orphan.example.uk"]
+    );
+    assert_eq!(
+        PrivateList::from(list).as_ref().dangling_exceptions(),
+        ["!other.kawasaki.jp"]
+    );
+}
+
+#[test]
+fn diff() {
+    let list = "// ===BEGIN ICANN DOMAINS===
+        uk
+        co.uk
+        *.ck
+        !www.ck
+        // ===BEGIN PRIVATE DOMAINS===
+        github.io"
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let old = list.parse::<List>().unwrap();
+    let new_text = format!("// VERSION: 2\n{}", list)
+        .replace("co.uk", "org.uk")
+        .replace("!www.ck", "!web.ck")
+        .replace("github.io", "io\n*.github.io");
+    let new = new_text.parse::<List>().unwrap();
+    let rules = |rules: &[(&str, Type)]| {
+        rules
+            .iter()
+            .map(|(rule, typ)| (rule.to_string(), *typ))
+            .collect::<Vec<_>>()
+    };
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.added,
+        rules(&[
+            ("!web.ck", Type::Icann),
+            ("*.github.io", Type::Private),
+            ("io", Type::Private),
+            ("org.uk", Type::Icann),
+        ])
+    );
+    assert_eq!(
+        diff.removed,
+        rules(&[
+            ("!www.ck", Type::Icann),
+            ("co.uk", Type::Icann),
+            ("github.io", Type::Private),
+        ])
+    );
+    assert!(old.diff(&old).is_empty());
+    assert_eq!(new.diff(&old).added, diff.removed);
+
+    let icann = IcannList::from(old.clone());
+    assert_eq!(icann.as_ref().diff(&new).added.len(), 2);
+
+    let mut refreshed = old.clone();
+    assert_eq!(refreshed.refresh_from_str(&new_text), Ok(diff));
+    assert_eq!(refreshed, new);
+    assert_eq!(refreshed.meta().version.as_deref(), Some("2"));
+    assert!(refreshed.refresh_from_str("nonsense").is_err());
+    assert_eq!(refreshed, new);
+
+    // refreshing the real list against itself changes nothing
+    let mut list = LIST.clone();
+    let text = include_str!("public_suffix_list.dat");
+    assert!(list.refresh_from_str(text).unwrap().is_empty());
+    let mut list = List::new();
+    assert!(!list.refresh_from_str(text).unwrap().is_empty());
+    assert_eq!(list, *LIST);
+}
+
+#[test]
+fn diff_to_patch() {
+    let old = "// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io"
+        .parse::<List>()
+        .unwrap();
+    let new =
+        "// ===BEGIN ICANN DOMAINS===\nuk\norg.uk\n*.ck\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io"
+            .parse::<List>()
+            .unwrap();
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.to_patch(),
+        "// ===BEGIN ICANN DOMAINS===\n\
+         // REMOVED: co.uk\n\
+         *.ck\n\
+         org.uk\n\
+         // ===END ICANN DOMAINS===\n"
+    );
+    // the added rules survive a round trip, the removed ones are only noted
+    let patch = diff.to_patch().parse::<List>().unwrap();
+    assert_eq!(List::new().diff(&patch).added, diff.added);
+    assert!(patch.diff(&new).removed.is_empty());
+    assert_eq!(old.diff(&old).to_patch(), "");
+
+    // a patch of only removals has no rules
+    let patch = LIST.diff(&new).to_patch();
+    assert!(patch.contains("// REMOVED: co.uk\n"));
+    assert!(patch.contains("// ===BEGIN PRIVATE DOMAINS===\n"));
+    assert_eq!(patch.parse::<List>(), Err(Error::InvalidList));
+}
+
+#[test]
+fn affected_domains() {
+    let old = "// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\nio\n// ===BEGIN PRIVATE DOMAINS===\nblogspot.co.uk"
+        .parse::<List>()
+        .unwrap();
+    let new = "// ===BEGIN ICANN DOMAINS===\nuk\nio\nblogspot.co.uk\n// ===BEGIN PRIVATE DOMAINS===\nco.uk\ngithub.io"
+        .parse::<List>()
+        .unwrap();
+    let domains = [
+        "www.example.co.uk",
+        "foo.blogspot.co.uk",
+        "example.uk",
+        "foo.github.io",
+        "example.io",
+        "example.com",
+    ];
+    assert_eq!(
+        old.affected_domains(&new, &domains),
+        ["www.example.co.uk", "foo.blogspot.co.uk", "foo.github.io"]
+    );
+    assert_eq!(
+        new.affected_domains(&old, &domains),
+        old.affected_domains(&new, &domains)
+    );
+    assert!(old.affected_domains(&old.clone(), &domains).is_empty());
+    assert!(LIST.affected_domains(&LIST, &domains).is_empty());
+}
+
+#[test]
+fn list_builder() {
+    let parsed: List = "// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\n*.kawasaki.jp\n!city.kawasaki.jp\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n"
+        .parse()
+        .unwrap();
+    let built = ListBuilder::new()
+        .begin_icann()
+        .rule("uk")
+        .unwrap()
+        .rule("co.uk")
+        .unwrap()
+        .rule("*.kawasaki.jp")
+        .unwrap()
+        .rule("!city.kawasaki.jp")
+        .unwrap()
+        .begin_private()
+        .rule("github.io")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(built, parsed);
+    assert_eq!(built.diff(&parsed), Default::default());
+
+    let mut builder = ListBuilder::new();
+    assert!(matches!(
+        builder.rule("com"),
+        Err(Error::RuleBeforeSection(rule)) if rule == "com"
+    ));
+    assert!(matches!(
+        builder.begin_icann().rule("co..uk"),
+        Err(Error::EmptyLabel(_))
+    ));
+    assert!(matches!(builder.build(), Err(Error::InvalidList)));
+}
+
+#[test]
+fn from_sources() {
+    let official = "// VERSION: 1\n// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\n// ===BEGIN PRIVATE DOMAINS===\ngithub.io";
+    let own = "// VERSION: 2\n// ===BEGIN PRIVATE DOMAINS===\ninternal.co.uk\ngithub.io\n// ===BEGIN ICANN DOMAINS===\ncorp";
+    let list = List::from_sources(&[official, own]).unwrap();
+    assert_eq!(list.suffix_type("www.example.co.uk"), Some(Type::Icann));
+    assert_eq!(list.suffix_type("foo.internal.co.uk"), Some(Type::Private));
+    assert_eq!(list.suffix_type("foo.corp"), Some(Type::Icann));
+    assert_eq!(list.meta().version.as_deref(), Some("2"));
+
+    // every source starts outside of any section
+    let error = List::from_sources(&[official, "com"]).unwrap_err();
+    assert_eq!(error, Error::RuleBeforeSection("com".into()));
+    assert_eq!(List::from_sources(&[]), Err(Error::InvalidList));
+
+    #[cfg(feature = "sources")]
+    {
+        assert_eq!(list.rule_source("co.uk"), Some(0));
+        assert_eq!(list.rule_source("internal.co.uk"), Some(1));
+        assert_eq!(list.rule_source("corp"), Some(1));
+        // a rule in both sources comes from the last one
+        assert_eq!(list.rule_source("github.io"), Some(1));
+        assert_eq!(list.rule_source("example.co.uk"), None);
+        assert_eq!(LIST.rule_source("co.uk"), None);
+    }
+}
+
+#[test]
+fn map_types() {
+    let mut list = LIST.clone();
+    list.map_types(|_| Type::Icann);
+    let mut count = 0;
+    list.walk(|_, info| {
+        assert_eq!(info.typ, Type::Icann);
+        count += 1;
+    });
+    let mut expected = 0;
+    LIST.walk(|_, _| expected += 1);
+    assert_eq!(count, expected);
+    let icann = IcannList::from(list.clone());
+    assert_eq!(icann.suffix(b"foo.github.io").unwrap(), "github.io");
+    assert_eq!(
+        icann.suffix(b"www.city.kawasaki.jp").unwrap().typ(),
+        Some(Type::Icann)
+    );
+
+    // swapping the types twice gives back the original list
+    let swap = |typ| match typ {
+        Type::Icann => Type::Private,
+        Type::Private => Type::Icann,
+    };
+    let mut list = LIST.clone();
+    list.map_types(swap);
+    assert_eq!(list.suffix_type("www.example.co.uk"), Some(Type::Private));
+    assert_eq!(list.suffix_type("foo.github.io"), Some(Type::Icann));
+    list.map_types(swap);
+    assert_eq!(list, *LIST);
+}
+
+#[test]
+fn exceptions() {
+    let exceptions = LIST.exceptions().collect::<Vec<_>>();
+    assert!(exceptions.iter().all(|rule| rule.starts_with('!')));
+    assert!(exceptions.contains(&"!city.kawasaki.jp".to_owned()));
+    assert!(exceptions.contains(&"!www.ck".to_owned()));
+    assert_eq!(exceptions.len(), 8);
+    assert_eq!(LIST.exceptions().collect::<Vec<_>>(), exceptions);
+    for rule in &exceptions {
+        assert!(LIST.rule_info(rule).unwrap().is_exception, "{}", rule);
+    }
+    assert_eq!(
+        PrivateList::from(LIST.clone())
+            .as_ref()
+            .exceptions()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn overlapping_wildcards() {
+    let list = "// ===BEGIN PRIVATE DOMAINS===\n\
+        *.platform.sh\n\
+        specific.platform.sh\n\
+        *.deep.platform.sh\n\
+        *.sub.platform.sh\n\
+        !www.sub.platform.sh"
+        .parse::<List>()
+        .unwrap();
+    let blob = StaticList::build(&list).unwrap();
+    let static_list = StaticList::new(&blob).unwrap();
+    for (name, suffix, rule) in [
+        ("a.foo.platform.sh", "foo.platform.sh", "*.platform.sh"),
+        (
+            "a.specific.platform.sh",
+            "specific.platform.sh",
+            "specific.platform.sh",
+        ),
+        (
+            "a.b.deep.platform.sh",
+            "b.deep.platform.sh",
+            "*.deep.platform.sh",
+        ),
+        // `deep` has no rule of its own, but `*` still matches it
+        ("deep.platform.sh", "deep.platform.sh", "*.platform.sh"),
+        (
+            "a.deep.platform.sh",
+            "a.deep.platform.sh",
+            "*.deep.platform.sh",
+        ),
+        // the exception prevails over `*.platform.sh` matching `sub`
+        (
+            "www.sub.platform.sh",
+            "sub.platform.sh",
+            "!www.sub.platform.sh",
+        ),
+        (
+            "a.b.sub.platform.sh",
+            "b.sub.platform.sh",
+            "*.sub.platform.sh",
+        ),
+    ] {
+        assert_eq!(list.lookup(name).unwrap().suffix(), suffix, "{}", name);
+        assert_eq!(list.matching_rule(name).as_deref(), Some(rule), "{}", name);
+        let candidate = list
+            .matches_iter(name)
+            .filter(|candidate| candidate.rule().is_some())
+            .last()
+            .unwrap();
+        if !rule.starts_with('!') {
+            assert_eq!(candidate.suffix(), suffix, "{}", name);
+        }
+        assert_eq!(
+            static_list.find(labels_bytes(name.as_bytes())),
+            list.find(labels_bytes(name.as_bytes())),
+            "{}",
+            name
+        );
+    }
+    assert!(list.lookup("deep.platform.sh").unwrap().is_wildcard_match());
+
+    // the same happens in the real list
+    assert_eq!(LIST.suffix(b"in.futurecms.at").unwrap(), "in.futurecms.at");
+    assert_eq!(LIST.domain(b"in.futurecms.at"), None);
+    assert_eq!(
+        LIST.domain(b"www.foo.in.futurecms.at").unwrap(),
+        "www.foo.in.futurecms.at"
+    );
+    assert_eq!(
+        LIST.domain(b"a.b.svc.firenet.ch").unwrap(),
+        "a.b.svc.firenet.ch"
+    );
+}
+
+#[test]
+fn many_wildcard_paths() {
+    // every combination of a label and a `*` under `x`, so a lookup of
+    // `d.c.b.a.x` follows 16 paths at once, and only the last one, through
+    // nothing but `*` labels, leads to the longest rule
+    let mut rules = vec![
+        "// BEGIN ICANN DOMAINS".to_string(),
+        "f.*.*.*.*.x".to_string(),
+    ];
+    for i in 0..16 {
+        let rule = ["a", "b", "c", "d"]
+            .iter()
+            .enumerate()
+            .map(|(bit, label)| if i & (1 << bit) == 0 { *label } else { "*" })
+            .rev()
+            .collect::<Vec<_>>()
+            .join(".");
+        rules.push(format!("{}.x", rule));
+    }
+    let list = rules.join("\n").parse::<List>().unwrap();
+    let blob = StaticList::build(&list).unwrap();
+    let static_list = StaticList::new(&blob).unwrap();
+    let name = "www.f.d.c.b.a.x";
+    assert_eq!(list.lookup(name).unwrap().suffix(), "f.d.c.b.a.x");
+    assert_eq!(list.matching_rule(name).as_deref(), Some("f.*.*.*.*.x"));
+    let candidate = list
+        .matches_iter(name)
+        .filter(|candidate| candidate.rule().is_some())
+        .last()
+        .unwrap();
+    assert_eq!(candidate.suffix(), "f.d.c.b.a.x");
+    assert_eq!(static_list.suffix(name.as_bytes()).unwrap(), "f.d.c.b.a.x");
+}
+
+#[test]
+fn suffix_under() {
+    assert!(LIST.suffix_under("foo.co.uk", "uk"));
+    assert!(LIST.suffix_under("foo.co.uk", "co.uk"));
+    assert!(LIST.suffix_under("FOO.CO.UK.", "Uk."));
+    assert!(LIST.suffix_under("example.gafregsrse", "gafregsrse"));
+    assert!(!LIST.suffix_under("foo.co.uk", "k"));
+    assert!(!LIST.suffix_under("foo.co.uk", "foo.co.uk"));
+    assert!(!LIST.suffix_under("foo.co.uk", "com"));
+    assert!(!LIST.suffix_under("foo.co.uk", ""));
+    assert!(!LIST.suffix_under("", "uk"));
+    // the length of `a` falls inside a character of the suffix
+    assert!(!LIST.suffix_under("www.食狮.中国", "a"));
+    assert!(LIST.suffix_under("www.食狮.中国", "中国"));
+}
+
+#[test]
+fn double_hyphen_labels() {
+    let mut list = "// ===BEGIN ICANN DOMAINS===
+        a--b
+        ab--cd.example
+        *.x--y.example
+        xn--zz.example
+        食狮--x.中国"
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .parse::<List>()
+        .unwrap();
+    assert_eq!(list.suffix(b"www.a--b").unwrap(), "a--b");
+    assert_eq!(
+        list.suffix(b"www.ab--cd.example").unwrap(),
+        "ab--cd.example"
+    );
+    assert_eq!(
+        list.suffix(b"www.foo.x--y.example").unwrap(),
+        "foo.x--y.example"
+    );
+    assert_eq!(
+        list.suffix(b"www.xn--zz.example").unwrap(),
+        "xn--zz.example"
+    );
+    assert_eq!(
+        list.suffix("www.食狮--x.中国".as_bytes()).unwrap(),
+        "食狮--x.中国"
+    );
+    #[cfg(feature = "punycode")]
+    assert_eq!(
+        list.suffix(b"www.xn----x-5w9gy23n.xn--fiqs8s").unwrap(),
+        "xn----x-5w9gy23n.xn--fiqs8s"
+    );
+    assert_eq!(list.insert_icann("xn--abc"), Ok(true));
+    assert_eq!(list.suffix(b"www.xn--abc").unwrap(), "xn--abc");
+    assert_eq!(list.insert_icann("xn--abc"), Ok(false));
+}
+
+#[test]
+fn common_suffix() {
+    assert_eq!(
+        LIST.common_suffix(vec!["example.co.uk", "example.org.uk"]),
+        Some("uk".to_owned())
+    );
+    assert_eq!(
+        LIST.common_suffix(vec!["a.example.co.uk", "b.example.co.uk."]),
+        Some("co.uk".to_owned())
+    );
+    assert_eq!(
+        LIST.common_suffix(vec!["example.co.uk", "EXAMPLE.Co.Uk"]),
+        Some(
+            if cfg!(any(feature = "anycase", feature = "ascii-case")) {
+                "co.uk"
+            } else {
+                "uk"
+            }
+            .to_owned()
+        )
+    );
+    assert_eq!(
+        LIST.common_suffix(vec!["a.b.kawasaki.jp", "c.d.kawasaki.jp", "e.ac.jp"]),
+        Some("jp".to_owned())
+    );
+    assert_eq!(
+        LIST.common_suffix(iter::once("www.example.com")),
+        Some("com".to_owned())
+    );
+    assert_eq!(LIST.common_suffix(vec!["example.com", "example.org"]), None);
+    assert_eq!(LIST.common_suffix(vec!["example.com", ""]), None);
+    assert_eq!(LIST.common_suffix(iter::empty()), None);
+}
+
+#[test]
+fn suffix_rule_kind() {
+    assert_eq!(
+        LIST.suffix_rule_kind("city.kawasaki.jp"),
+        Some(RuleKind::Exception)
+    );
+    assert_eq!(
+        LIST.suffix_rule_kind("foo.kawasaki.jp"),
+        Some(RuleKind::Wildcard)
+    );
+    assert_eq!(LIST.suffix_rule_kind("example.com"), Some(RuleKind::Normal));
+    assert_eq!(
+        LIST.suffix_rule_kind("example.gafregsrse"),
+        Some(RuleKind::Wildcard)
+    );
+    assert_eq!(LIST.suffix_rule_kind(""), None);
+}
+
+#[test]
+fn matches_iter() {
+    let steps = |input| {
+        LIST.matches_iter(input)
+            .map(|candidate| {
+                (
+                    candidate.suffix(),
+                    candidate.rule().map(|rule| rule.is_exception),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        steps("www.city.kawasaki.jp"),
+        [
+            ("jp", Some(false)),
+            ("kawasaki.jp", None),
+            ("city.kawasaki.jp", Some(true)),
+            ("www.city.kawasaki.jp", None),
+        ]
+    );
+    assert_eq!(
+        steps("foo.bar.kawasaki.jp."),
+        [
+            ("jp.", Some(false)),
+            ("kawasaki.jp.", None),
+            ("bar.kawasaki.jp.", Some(false)),
+            ("foo.bar.kawasaki.jp.", None),
+        ]
+    );
+    assert_eq!(
+        steps("example.gafregsrse"),
+        [("gafregsrse", None), ("example.gafregsrse", None)]
+    );
+    assert!(steps("").is_empty());
+    assert!(steps(".").is_empty());
+    #[cfg(feature = "std")]
+    assert!(steps("127.0.0.1").is_empty());
+
+    let icann = IcannList::from(LIST.clone());
+    let types = icann
+        .as_ref()
+        .matches_iter("foo.github.io")
+        .map(|candidate| candidate.rule().map(|rule| rule.typ))
+        .collect::<Vec<_>>();
+    assert_eq!(types, [Some(Type::Icann), None, None]);
+}
+
+#[test]
+fn rule_info() {
+    let info = LIST.rule_info("*.kawasaki.jp").unwrap();
+    assert!(!info.is_exception);
+    assert_eq!(info.typ, Type::Icann);
+    assert!(LIST.rule_info("!city.kawasaki.jp").unwrap().is_exception);
+    assert_eq!(LIST.rule_info("city.kawasaki.jp"), None);
+    assert_eq!(LIST.rule_info("foo.kawasaki.jp"), None);
+    assert_eq!(LIST.rule_info("github.io").unwrap().typ, Type::Private);
+    assert_eq!(LIST.rule_info("example.com"), None);
+    assert_eq!(LIST.rule_info("!com"), None);
+    assert_eq!(LIST.rule_info(""), None);
+
+    let icann = IcannList::from(LIST.clone());
+    assert_eq!(icann.as_ref().rule_info("github.io"), None);
+    assert!(icann.as_ref().rule_info("co.uk").is_some());
+}
+
+#[test]
+fn lookup_relaxed() {
+    let found = LIST.lookup_relaxed("www.example.co.uk.").unwrap();
+    assert_eq!(found.suffix(), "co.uk");
+    assert_eq!(found.registrable_domain(), Some("example.co.uk"));
+    assert_eq!(
+        LIST.lookup_relaxed("example.co.uk").unwrap().suffix(),
+        "co.uk"
+    );
+    assert!(LIST.lookup_relaxed("example.com..").is_none());
+    assert!(LIST.lookup_relaxed(".example.com").is_none());
+    assert!(LIST.lookup_relaxed("example..com").is_none());
+    assert!(LIST.lookup_relaxed(".").is_none());
+    assert!(LIST.lookup_relaxed("").is_none());
+}
+
+#[test]
+fn lookup_host_header() {
+    let suffix = |value| LIST.lookup_host_header(value).map(|found| found.suffix());
+    assert_eq!(suffix("example.com:443"), Some("com"));
+    assert_eq!(suffix("  example.com  "), Some("com"));
+    assert_eq!(suffix("\twww.example.co.uk.:8080 "), Some("co.uk"));
+    assert_eq!(
+        LIST.lookup_host_header("www.example.co.uk:80")
+            .and_then(|found| found.registrable_domain()),
+        Some("example.co.uk")
+    );
+    for value in [
+        "[::1]:8080",
+        "[::1]",
+        "::1",
+        "",
+        "   ",
+        ":443",
+        "example.com:",
+        "example.com:https",
+        "exa mple.com",
+        "example..com",
+    ] {
+        assert_eq!(suffix(value), None, "{:?}", value);
+    }
+    #[cfg(feature = "std")]
+    assert_eq!(suffix("127.0.0.1:8080"), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_match() {
+    let json = |input| serde_json::to_string(&LIST.lookup(input).unwrap()).unwrap();
+    assert_eq!(
+        json("www.example.co.uk"),
+        r#"{"suffix":"co.uk","type":"ICANN","registrable_domain":"example.co.uk"}"#
+    );
+    assert_eq!(
+        json("github.io"),
+        r#"{"suffix":"github.io","type":"PRIVATE","registrable_domain":null}"#
+    );
+    assert_eq!(
+        json("example.gafregsrse"),
+        r#"{"suffix":"gafregsrse","type":null,"registrable_domain":"example.gafregsrse"}"#
+    );
+}
+
+#[test]
+fn from_iana_tlds() {
+    let tlds = "# Version 2024062700, Last Updated Thu Jun 27 07:07:01 2024 UTC
+        COM
+        UK
+        XN--FIQS8S
+
+        JP"
+    .lines()
+    .collect::<Vec<_>>();
+    let list = List::from_iana_tlds(&tlds).with_special_use();
+    assert_eq!(
+        list.iter_tlds().collect::<Vec<_>>(),
+        [
+            "com",
+            "example",
+            "invalid",
+            "jp",
+            "local",
+            "localhost",
+            "test",
+            "uk",
+            "xn--fiqs8s"
+        ]
+    );
+    assert_eq!(list.suffix_type("www.example.com"), Some(Type::Icann));
+    assert_eq!(list.suffix(b"www.example.co.uk").unwrap(), "uk");
+    assert_eq!(list.suffix(b"foo.xn--fiqs8s").unwrap(), "xn--fiqs8s");
+    assert_eq!(list.suffix_type("foo.localhost"), Some(Type::Icann));
+    assert!(List::from_iana_tlds(&[]).is_empty());
+}
+
+#[test]
+fn collect_rules() {
+    let rules = vec![
+        ("co.uk".to_string(), Type::Icann),
+        ("*.platformsh.site".to_string(), Type::Private),
+        ("..invalid".to_string(), Type::Icann),
+    ];
+    let list: List = rules.into_iter().collect();
+    assert_eq!(list.suffix(b"example.co.uk").unwrap(), "co.uk");
+    assert_eq!(
+        list.suffix_type("foo.bar.platformsh.site"),
+        Some(Type::Private)
+    );
+    assert_eq!(list.suffix_type("example.invalid"), None);
+}
+
+#[test]
+fn known_tlds() {
+    assert!(LIST.is_known_tld("com"));
+    assert!(LIST.is_known_tld("uk"));
+    assert!(LIST.is_known_tld("中国"));
+    assert!(!LIST.is_known_tld("gafregsrse"));
+    assert!(!LIST.is_known_tld("co.uk"));
+    assert!(!LIST.is_known_tld(""));
+    #[cfg(any(feature = "anycase", feature = "ascii-case"))]
+    assert!(LIST.is_known_tld("COM"));
+}
+
+#[test]
+fn suggest_tld() {
+    assert_eq!(LIST.suggest_tld("comapny"), ["company"]);
+    let suggestions = LIST.suggest_tld("con");
+    assert!(suggestions.len() <= 5);
+    assert!(suggestions.iter().any(|tld| tld == "com"));
+    assert!(suggestions.iter().all(|tld| LIST.is_known_tld(tld)));
+    assert!(LIST.suggest_tld("com").is_empty());
+    assert!(LIST.suggest_tld("").is_empty());
+    assert!(LIST.suggest_tld("gafregsrse").is_empty());
+
+    let list = "// ===BEGIN ICANN DOMAINS===\ncom\nnet\norg\nexample\nexpert"
+        .parse::<List>()
+        .unwrap();
+    assert_eq!(list.suggest_tld("con"), ["com"]);
+    assert_eq!(list.suggest_tld("nte"), Vec::<String>::new());
+    assert_eq!(list.suggest_tld("exampel"), ["example"]);
+    assert_eq!(list.suggest_tld("expart"), ["expert"]);
+    assert_eq!(list.suggest_tld("exper"), ["expert"]);
+    assert_eq!(list.suggest_tld("xeample"), ["example"]);
+    if !cfg!(any(feature = "anycase", feature = "ascii-case")) {
+        assert_eq!(list.suggest_tld("COM"), ["com"]);
+    }
+}
+
+#[test]
+fn tld_histogram() {
+    let histogram = LIST.tld_histogram();
+    assert!(histogram
+        .windows(2)
+        .all(|pair| pair[0].1 > pair[1].1 || (pair[0].1 == pair[1].1 && pair[0].0 < pair[1].0)));
+    let mut rules = 0;
+    LIST.walk(|_, _| rules += 1);
+    assert_eq!(
+        histogram.iter().map(|(_, count)| count).sum::<usize>(),
+        rules
+    );
+    assert!(histogram[..5].iter().any(|(tld, _)| tld == "jp"));
+
+    let list = "// ===BEGIN ICANN DOMAINS===
+        uk
+        co.uk
+        org.uk
+        *.ck
+        !www.ck
+        com
+        // ===BEGIN PRIVATE DOMAINS===
+        github.io"
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .parse::<List>()
+        .unwrap();
+    let histogram = |list: &List| {
+        list.tld_histogram()
+            .into_iter()
+            .map(|(tld, count)| format!("{}:{}", tld, count))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(histogram(&list), ["uk:3", "ck:2", "com:1", "io:1"]);
+    let icann = IcannList::from(list);
+    assert_eq!(histogram(icann.as_ref()), ["uk:3", "ck:2", "com:1"]);
+}
+
+#[test]
+fn retain_tlds() {
+    let mut list = LIST.clone();
+    list.retain_tlds(&["uk", "de", "fr", "gafregsrse"]);
+    assert_eq!(list.iter_tlds().collect::<Vec<_>>(), ["de", "fr", "uk"]);
+    assert_eq!(list.suffix_type("www.example.co.uk"), Some(Type::Icann));
+    assert_eq!(list.lookup("foo.bar.co.uk").unwrap().suffix(), "co.uk");
+    let found = list.lookup("www.example.com").unwrap();
+    assert_eq!(found.suffix(), "com");
+    assert!(found.is_implicit_wildcard());
+    assert!(LIST.lookup("www.example.com").unwrap().is_known());
+
+    list.retain_tlds(&[]);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn extract_subtree() {
+    let jp = LIST.extract_subtree("jp").unwrap();
+    assert_eq!(jp.iter_tlds().collect::<Vec<_>>(), ["jp"]);
+    for name in ["foo.co.jp", "www.city.kawasaki.jp", "a.b.kawasaki.jp", "jp"] {
+        assert_eq!(jp.lookup(name), LIST.lookup(name));
+    }
+    let found = jp.lookup("www.example.com").unwrap();
+    assert_eq!(found.suffix(), "com");
+    assert!(found.is_implicit_wildcard());
+
+    let mut retained = LIST.clone();
+    retained.retain_tlds(&["jp"]);
+    assert_eq!(jp, retained);
+    assert_eq!(LIST.extract_subtree("gafregsrse"), None);
+}
+
+#[test]
+fn icann_only() {
+    let list = include_str!("public_suffix_list.dat");
+    let icann_only = IcannList::from_str_icann_only(list).unwrap();
+    let icann = IcannList::from(LIST.clone());
+    for name in [
+        "foo.github.io",
+        "www.example.co.uk",
+        "www.city.kawasaki.jp",
+        "a.b.c",
+    ] {
+        assert_eq!(
+            icann_only.as_ref().lookup(name),
+            icann.as_ref().lookup(name)
+        );
+    }
+    let mut rules = 0;
+    icann_only.as_ref().walk(|_, rule| {
+        assert_eq!(rule.typ, Type::Icann);
+        rules += 1;
+    });
+    let mut icann_rules = 0;
+    icann.as_ref().walk(|_, _| icann_rules += 1);
+    assert_eq!(rules, icann_rules);
+    assert!(IcannList::from_str_icann_only("// BEGIN PRIVATE DOMAINS\nfoo.uk").is_err());
+}
+
+#[test]
+fn suffix_sections() {
+    assert!(LIST.suffix_is_private("foo.github.io"));
+    assert!(!LIST.suffix_is_icann("foo.github.io"));
+    assert!(LIST.suffix_is_icann("foo.com"));
+    assert!(!LIST.suffix_is_private("foo.com"));
+    assert_eq!(LIST.suffix_type("example.gafregsrse"), None);
+    assert!(!LIST.suffix_is_icann("example.gafregsrse"));
+    assert!(!LIST.suffix_is_private("example.gafregsrse"));
+}
+
+#[test]
+fn by_owner() {
+    let list = PrivateList::from_bytes(include_bytes!("public_suffix_list.dat")).unwrap();
+    let owners = list.by_owner();
+    assert_eq!(
+        owners["Adobe"],
+        [
+            "adobeaemcloud.com",
+            "adobeaemcloud.net",
+            "*.dev.adobeaemcloud.com"
+        ]
+    );
+    assert_eq!(owners["Altervista"], ["altervista.org"]);
+    assert!(owners["GitHub, Inc."]
+        .iter()
+        .any(|rule| rule == "github.io"));
+    // rules that only appear in the ICANN section are never included
+    assert!(owners.values().flatten().all(|rule| rule != "co.uk"));
+    // the note right after the section marker isn't an owner
+    assert!(!owners.keys().any(|owner| owner.starts_with("(Note")));
+    for rule in owners.values().flatten() {
+        assert!(list.as_ref().rule_info(rule).is_some(), "{}", rule);
+    }
+
+    // owners are saved along with the list
+    #[cfg(feature = "std")]
+    {
+        let mut bytes = Vec::new();
+        LIST.save(&mut bytes).unwrap();
+        let loaded = PrivateList::from(List::load(&bytes[..]).unwrap());
+        assert_eq!(loaded.by_owner(), owners);
+    }
+
+    // rules that are gone or no longer private are left out
+    let mut list = List::from(list);
+    list.subtract(&"// BEGIN PRIVATE DOMAINS\naltervista.org".parse().unwrap());
+    let without = PrivateList::from(list.clone()).by_owner();
+    assert!(!without.contains_key("Altervista"));
+    assert_eq!(without["Adobe"], owners["Adobe"]);
+    list.map_types(|_| Type::Icann);
+    assert!(PrivateList::from(list).by_owner().is_empty());
+    let list: List =
+        "// BEGIN PRIVATE DOMAINS\n// Foo: https://foo.example\nfoo.example\n\nbar.example"
+            .parse()
+            .unwrap();
+    let owners = PrivateList::from(list).by_owner();
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners["Foo"], ["foo.example"]);
+}
+
+#[test]
+fn types() {
+    for typ in publicsuffix::types() {
+        let mut count = 0;
+        LIST.walk(|_, rule| count += usize::from(rule.typ == typ));
+        assert!(count > 0);
+    }
+    assert_eq!(publicsuffix::types(), [Type::Icann, Type::Private]);
+}
+
+#[test]
+fn subtract() {
+    let mut list = LIST.clone();
+    let icann = IcannList::from(LIST.clone());
+    list.subtract(icann.as_ref());
+    assert!(!list.is_empty());
+    assert_eq!(list.suffix_type("example.co.uk"), None);
+    assert_eq!(list.suffix(b"example.co.uk").unwrap(), "uk");
+    assert_eq!(list.suffix_type("foo.github.io"), Some(Type::Private));
+    assert_eq!(list.suffix(b"foo.github.io").unwrap(), "github.io");
+
+    list.subtract(&LIST);
+    assert!(list.is_empty());
+}
+
+#[cfg(feature = "config")]
+#[test]
+fn from_config() {
+    let json = r#"{ "icann": ["internal", "*.dev.internal"], "private": ["team.internal"] }"#;
+    let toml = r#"
+        icann = ["internal", "*.dev.internal"]
+        private = ["team.internal"]
+    "#;
+    for list in [List::from_json(json), List::from_toml(toml)] {
+        let list = list.unwrap();
+        assert_eq!(list.suffix_type("a.internal"), Some(Type::Icann));
+        assert_eq!(list.suffix_type("a.team.internal"), Some(Type::Private));
+        assert_eq!(list.suffix(b"a.b.dev.internal").unwrap(), "b.dev.internal");
+    }
+
+    let list = List::from_json(r#"{ "private": ["team.internal"] }"#).unwrap();
+    assert!(list.is_private_only());
+    assert_eq!(List::from_json("{}"), Err(Error::InvalidList));
+    assert_eq!(
+        List::from_json(r#"{ "icann": ["internal"], "private": ["ok.internal", "a..b"] }"#),
+        Err(Error::InvalidConfig(
+            "`private[1]`: rule `a..b` contains an empty label".to_owned()
+        ))
+    );
+    assert_eq!(
+        List::from_toml("icann = [\"!internal\"]"),
+        Err(Error::InvalidConfig(
+            "`icann[0]`: `!internal`; exceptions only valid at end of rule".to_owned()
+        ))
+    );
+    match List::from_json("{\n  \"icann\": [\"internal\",]\n}") {
+        Err(Error::InvalidConfig(reason)) => assert!(reason.contains("line 2"), "{}", reason),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(
+        List::from_toml("icann = [\"internal\"]\nbogus = 1"),
+        Err(Error::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn static_list() {
+    let blob = StaticList::build(&LIST).unwrap();
+    let list = StaticList::new(&blob).unwrap();
+    assert!(!list.is_empty());
+
+    let inputs = include_str!("tests.txt")
+        .lines()
+        .filter(|line| !line.starts_with("//"))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_lowercase)
+        .chain(
+            [
+                "www.example.co.uk",
+                "a.b.kawasaki.jp",
+                "city.kawasaki.jp",
+                "www.ck",
+                "foo.github.io",
+                "example.gafregsrse",
+                "kawasaki.jp",
+                "jp",
+                "",
+            ]
+            .iter()
+            .map(|input| input.to_string()),
+        );
+    for input in inputs {
+        let name = || labels_bytes(input.as_bytes());
+        assert_eq!(list.find(name()), LIST.find(name()), "{}", input);
+    }
+
+    let icann = IcannList::from(LIST.clone());
+    let blob = StaticList::build(icann.as_ref()).unwrap();
+    let list = StaticList::new(&blob).unwrap();
+    assert_eq!(list.suffix(b"foo.github.io").unwrap(), "io");
+
+    assert_eq!(StaticList::new(b"PSL\0"), Err(Error::UnsupportedFormat));
+    let mut blob = StaticList::build(&LIST).unwrap();
+    blob.truncate(blob.len() - 1);
+    assert_eq!(StaticList::new(&blob), Err(Error::InvalidList));
+}
+
+#[test]
+fn shared_list() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SharedList>();
+
+    let shared = SharedList::from(LIST.clone());
+    let clone = shared.clone();
+    assert_eq!(clone, shared);
+    assert_eq!(clone.domain(b"www.example.co.uk").unwrap(), "example.co.uk");
+    assert_eq!(
+        clone.as_ref().lookup("foo.github.io").unwrap().suffix(),
+        "github.io"
+    );
+
+    let private = SharedList::from(List::from(PrivateList::from(LIST.clone())));
+    assert_eq!(private.suffix(b"foo.github.io").unwrap(), "github.io");
+
+    // the rules are copied only while other clones are alive
+    assert_eq!(List::from(shared), *LIST);
+    assert_eq!(List::from(clone), *LIST);
+}
+
+#[cfg(feature = "cache")]
+#[test]
+fn cached_list() {
+    use publicsuffix::CachedList;
+    use std::num::NonZeroUsize;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CachedList>();
+
+    let cached = CachedList::new(LIST.clone(), NonZeroUsize::new(2).unwrap());
+    assert!(cached.is_empty());
+    for _ in 0..2 {
+        assert_eq!(
+            cached.domain(b"www.example.co.uk").unwrap(),
+            "example.co.uk"
+        );
+        assert_eq!(cached.suffix(b"foo.github.io").unwrap(), "github.io");
+        assert_eq!(cached.len(), 2);
+    }
+    assert_eq!(
+        cached.find(labels_bytes(b"www.example.co.uk")),
+        LIST.find(labels_bytes(b"www.example.co.uk"))
+    );
+    assert_eq!(cached.find(iter::empty()), LIST.find(iter::empty()));
+
+    // the name looked up least recently is forgotten first
+    assert_eq!(cached.suffix(b"a.b.kawasaki.jp").unwrap(), "b.kawasaki.jp");
+    assert_eq!(cached.len(), 2);
+    cached.clear();
+    assert!(cached.is_empty());
+    assert_eq!(cached.into_inner(), *LIST);
+}
+
+#[test]
+fn psl_by_reference() {
+    fn suffix<P: Psl>(psl: P, name: &str) -> Option<String> {
+        let suffix = psl.suffix(name.as_bytes())?;
+        Some(str::from_utf8(suffix.as_bytes()).unwrap().to_owned())
+    }
+
+    let icann = IcannList::from(LIST.clone());
+    let private = PrivateList::from(LIST.clone());
+    assert_eq!(
+        suffix(&*LIST, "foo.github.io").as_deref(),
+        Some("github.io")
+    );
+    assert_eq!(suffix(&icann, "foo.github.io").as_deref(), Some("io"));
+    assert_eq!(
+        suffix(&private, "foo.github.io").as_deref(),
+        Some("github.io")
+    );
+    assert_eq!(suffix(icann, "example.co.uk").as_deref(), Some("co.uk"));
+}
+
+#[test]
+fn psl_implementations_agree() {
+    // the same calls other implementations of the trait, like the `psl`
+    // crate's, are made through
+    fn results<P: Psl + ?Sized>(psl: &P, names: &[String]) -> Vec<(Info, Option<Vec<u8>>)> {
+        names
+            .iter()
+            .map(|name| {
+                let info = psl.find(labels_bytes(name.as_bytes()));
+                let domain = psl.domain(name.as_bytes()).map(|d| d.as_bytes().to_vec());
+                (info, domain)
+            })
+            .collect()
+    }
+
+    let names = include_str!("tests.txt")
+        .lines()
+        .filter(|line| !line.starts_with("//"))
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| *name != "null")
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    let expected = results(&*LIST, &names);
+    assert_eq!(results(&&*LIST, &names), expected);
+    assert_eq!(results(&SharedList::from(LIST.clone()), &names), expected);
+    #[cfg(feature = "cache")]
+    {
+        let cached =
+            publicsuffix::CachedList::new(LIST.clone(), std::num::NonZeroUsize::new(64).unwrap());
+        assert_eq!(results(&cached, &names), expected);
+        assert_eq!(results(&cached, &names), expected);
+    }
+    let blob = StaticList::build(&LIST).unwrap();
+    assert_eq!(results(&StaticList::new(&blob).unwrap(), &names), expected);
+    let boxed: Box<dyn DynPsl> = Box::new(LIST.clone());
+    assert_eq!(results(&*boxed, &names), expected);
+}
+
+#[test]
+fn dyn_psl() {
+    let blob = StaticList::build(&LIST).unwrap();
+    let lists: Vec<Box<dyn DynPsl>> = vec![
+        Box::new(LIST.clone()),
+        Box::new(&*LIST),
+        Box::new(IcannList::from(LIST.clone())),
+        Box::new(PrivateList::from(LIST.clone())),
+        Box::new(StaticList::new(&blob).unwrap()),
+    ];
+    for name in ["foo.github.io", "www.example.co.uk.", "a.b.kawasaki.jp", ""] {
+        let labels = || labels_bytes(name.as_bytes());
+        assert_eq!(lists[0].find_dyn(&mut labels()), LIST.find(labels()));
+        assert_eq!(
+            lists[0].suffix(name.as_bytes()),
+            LIST.suffix(name.as_bytes())
+        );
+        assert_eq!(
+            lists[1].domain(name.as_bytes()),
+            LIST.domain(name.as_bytes())
+        );
+        let icann = IcannList::from(LIST.clone());
+        assert_eq!(lists[2].find(labels()), icann.find(labels()));
+        let private = PrivateList::from(LIST.clone());
+        assert_eq!(lists[3].find(labels()), private.find(labels()));
+        assert_eq!(lists[4].find(labels()), LIST.find(labels()));
+    }
+
+    // lists can be shared between threads behind the trait object too
+    let shared: Box<dyn DynPsl + Send + Sync> = Box::new(SharedList::from(LIST.clone()));
+    let suffix = std::thread::spawn(move || {
+        shared
+            .suffix(b"foo.github.io")
+            .map(|suffix| suffix.as_bytes().to_vec())
+    });
+    assert_eq!(suffix.join().unwrap().as_deref(), Some(&b"github.io"[..]));
+}
+
 // Converts a String to &'static str
 //
 // This will leak memory but that's OK for our testing purposes
 fn msg(s: String) -> &'static str {
-    unsafe {
-        let ret = mem::transmute(&s as &str);
-        mem::forget(s);
-        ret
-    }
+    Box::leak(s.into_boxed_str())
 }
 
 fn val(s: &Option<String>) -> String {
     match *s {
         Some(ref v) => format!("should be `{}`", v),
-        None => format!("is invalid"),
+        None => "is invalid".to_string(),
     }
 }
 
 fn expected_tld(input: &str) -> bool {
-    let var = if let Ok(var) = env::var("PSL_TLD") {
-        var
-    } else {
-        String::new()
-    };
+    let var = env::var("PSL_TLD").unwrap_or_default();
     var.trim().is_empty() || input.trim().trim_end_matches('.').ends_with(&var)
 }
@@ -0,0 +1,155 @@
+use proptest::collection::{btree_map, vec};
+use proptest::prelude::*;
+use publicsuffix::{labels_bytes, List, Psl, RuleInfo, StaticList, Type};
+use std::collections::BTreeMap;
+
+// None of the generated rules share a TLD with this list
+const BASE: &str = "// BEGIN ICANN DOMAINS\ncom\nco.uk\n*.ck\n!www.ck";
+
+fn typ() -> impl Strategy<Value = Type> {
+    prop_oneof![Just(Type::Icann), Just(Type::Private)]
+}
+
+// Rules without the leading `!`, mapped to whether they are exceptions
+fn rules() -> impl Strategy<Value = BTreeMap<String, RuleInfo>> {
+    let label = prop_oneof![4 => "[a-z0-9]{1,5}", 1 => Just("*".to_string())];
+    let rule = ("x[a-z0-9]{0,4}", vec(label, 0..4)).prop_map(|(tld, mut labels)| {
+        labels.reverse();
+        labels.push(tld);
+        labels.join(".")
+    });
+    let info =
+        (any::<bool>(), typ()).prop_map(|(is_exception, typ)| RuleInfo { is_exception, typ });
+    btree_map(rule, info, 1..20).prop_map(|rules| {
+        rules
+            .into_iter()
+            .map(|(rule, mut info)| {
+                // exceptions need more than one label
+                info.is_exception &= rule.contains('.');
+                (rule, info)
+            })
+            .collect()
+    })
+}
+
+fn written(rule: &str, info: RuleInfo) -> String {
+    if info.is_exception {
+        format!("!{}", rule)
+    } else {
+        rule.to_string()
+    }
+}
+
+fn list_of(rules: &BTreeMap<String, RuleInfo>) -> List {
+    let mut list = List::new();
+    for (rule, info) in rules {
+        assert_eq!(
+            list.insert_checked(&written(rule, *info), info.typ),
+            Ok(true)
+        );
+    }
+    list
+}
+
+// Whether `rule` matches `name`, label by label from the right
+fn matches(rule: &str, name: &str) -> bool {
+    let (rule, name) = (rule.split('.').rev(), name.split('.').rev());
+    rule.clone().count() <= name.clone().count()
+        && rule
+            .zip(name)
+            .all(|(rule, name)| rule == "*" || rule == name)
+}
+
+// The number of labels in the suffix of `name`, the way the list's
+// algorithm describes it: an exception rule prevails over all others,
+// then the longest rule does and otherwise the implicit `*` rule
+fn suffix_labels(rules: &BTreeMap<String, RuleInfo>, name: &str) -> usize {
+    let matching = rules.iter().filter(|(rule, _)| matches(rule, name));
+    let labels = |rule: &String| rule.split('.').count();
+    let exception = matching
+        .clone()
+        .filter(|(_, info)| info.is_exception)
+        .map(|(rule, _)| labels(rule) - 1)
+        .min();
+    let longest = matching.map(|(rule, _)| labels(rule)).max();
+    exception.or(longest).unwrap_or(1)
+}
+
+// The names the rules match, along with their parents and children
+fn names(rules: &BTreeMap<String, RuleInfo>) -> Vec<String> {
+    let mut names = Vec::new();
+    for rule in rules.keys() {
+        let name = format!("a.{}", rule.replace('*', "w"));
+        let labels = name.split('.').collect::<Vec<_>>();
+        names.extend((0..labels.len()).map(|i| labels[i..].join(".")));
+    }
+    names
+}
+
+proptest! {
+    #[test]
+    fn inserted_rules_can_be_found(rules in rules()) {
+        let list = list_of(&rules);
+        prop_assert!(list.is_valid());
+        for (rule, info) in &rules {
+            prop_assert_eq!(list.rule_info(&written(rule, *info)), Some(*info));
+            // an exception matching the rule itself would prevail over it
+            let overridden = rules
+                .iter()
+                .any(|(other, info)| info.is_exception && matches(other, rule));
+            if !info.is_exception && !rule.contains('*') && !overridden {
+                let found = list.lookup(rule).unwrap();
+                prop_assert_eq!(found.suffix(), rule.as_str());
+                prop_assert_eq!(found.typ(), Some(info.typ));
+            }
+        }
+    }
+
+    #[test]
+    fn suffixes_follow_the_algorithm(rules in rules()) {
+        let list = list_of(&rules);
+        let blob = StaticList::build(&list).unwrap();
+        let static_list = StaticList::new(&blob).unwrap();
+        for name in names(&rules) {
+            prop_assert_eq!(list.suffix_label_count(&name), Some(suffix_labels(&rules, &name)));
+            prop_assert_eq!(
+                static_list.find(labels_bytes(name.as_bytes())),
+                list.find(labels_bytes(name.as_bytes()))
+            );
+        }
+    }
+
+    #[test]
+    fn walking_rebuilds_the_list(rules in rules()) {
+        let list = list_of(&rules);
+        let mut walked = BTreeMap::new();
+        list.walk(|labels, info| {
+            let mut labels = labels.to_vec();
+            labels.reverse();
+            walked.insert(labels.join("."), info);
+        });
+        prop_assert_eq!(&walked, &rules);
+        prop_assert_eq!(list_of(&walked), list);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn saved_lists_load_unchanged(rules in rules()) {
+        let mut list: List = BASE.parse().unwrap();
+        list.extend(rules.iter().map(|(rule, info)| (written(rule, *info), info.typ)));
+        let mut bytes = Vec::new();
+        list.save(&mut bytes).unwrap();
+        prop_assert_eq!(List::load(&bytes[..]).unwrap(), list);
+    }
+
+    #[test]
+    fn subtracting_inserted_rules_restores_the_list(rules in rules()) {
+        let base: List = BASE.parse().unwrap();
+        let mut list = base.clone();
+        for (rule, info) in &rules {
+            list.insert_checked(&written(rule, *info), info.typ).unwrap();
+        }
+        list.subtract(&list_of(&rules));
+        prop_assert_eq!(list, base);
+    }
+}